@@ -28,7 +28,7 @@ fn write_string<const N: usize>(v: &String) -> [u8; N] {
 
 #[inline]
 fn write_string_array<const M: usize, const N: usize>(v: &[String; N]) -> [[u8; M]; N] {
-    let values: Vec<[u8; M]> = v.iter().map(|s| write_string(s)).collect();
+    let values: Vec<[u8; M]> = v.iter().map(write_string).collect();
 
     let mut array = [[b' '; M]; N];
     array.copy_from_slice(&values);
@@ -46,7 +46,7 @@ fn read_string<const N: usize>(v: &[u8; N]) -> String {
 
 #[inline]
 fn read_string_array<const M: usize, const N: usize>(v: &[[u8; M]; N]) -> [String; N] {
-    let values: Vec<String> = v.iter().map(|b| read_string(b)).collect();
+    let values: Vec<String> = v.iter().map(read_string).collect();
 
     let mut array: [String; N] = array::from_fn(|_| String::new());
     array.clone_from_slice(&values);
@@ -306,8 +306,8 @@ impl SacBinary {
             nxsize: v.nxsize,
             nysize: v.nysize,
             iftype: v.iftype.into(),
-            idep: v.idep,
-            iztype: v.iztype,
+            idep: v.idep.into(),
+            iztype: v.iztype.into(),
             iinst: v.iinst,
             istreg: v.istreg,
             ievreg: v.ievreg,
@@ -388,8 +388,8 @@ impl SacHeader {
             nxsize: v.nxsize,
             nysize: v.nysize,
             iftype: v.iftype.into(),
-            idep: v.idep,
-            iztype: v.iztype,
+            idep: v.idep.into(),
+            iztype: v.iztype.into(),
             iinst: v.iinst,
             istreg: v.istreg,
             ievreg: v.ievreg,
@@ -0,0 +1,163 @@
+use alloc::format;
+
+use crate::binary::SAC_INT_UNDEF;
+use crate::error::{Result, SacError};
+use crate::header::SacHeader;
+
+/// A reference time as `(year, day of year, hour, minute, second, millisecond)`.
+pub type RefTime = (i32, u32, u32, u32, u32, u32);
+
+// Days from 1970-01-01 to January 1st of `year`
+#[inline]
+pub(crate) fn days_before_year(year: i32) -> i64 {
+    let y = year as i64 - 1;
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + 306;
+    era * 146097 + doe - 719468
+}
+
+#[inline]
+pub(crate) fn days_in_year(year: i32) -> u32 {
+    (days_before_year(year + 1) - days_before_year(year)) as u32
+}
+
+// Days in each month of `year`
+#[inline]
+fn month_lengths(year: i32) -> [u32; 12] {
+    let feb = if days_in_year(year) == 366 { 29 } else { 28 };
+    [31, feb, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
+}
+
+// Year and 1-based day of year for a count of days since 1970-01-01
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn year_and_day(days: i64) -> (i32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+
+    let mut year = (yoe + era * 400) as i32;
+    if mp >= 10 {
+        year += 1;
+    }
+
+    (year, (days - days_before_year(year) + 1) as u32)
+}
+
+impl SacHeader {
+    /// The reference time, or `None` if any of its fields is undefined.
+    pub fn reference_time(&self) -> Option<RefTime> {
+        let field = |v: i32| u32::try_from(v).ok();
+        Some((
+            Some(self.nzyear).filter(|v| *v != SAC_INT_UNDEF)?,
+            field(self.nzjday)?,
+            field(self.nzhour)?,
+            field(self.nzmin)?,
+            field(self.nzsec)?,
+            field(self.nzmsec)?,
+        ))
+    }
+
+    /// Sets `nzyear`..`nzmsec`. Fails, leaving the header alone, if a field is
+    /// out of range (e.g. day 366 of a common year).
+    pub fn set_reference_time(&mut self, t: RefTime) -> Result<()> {
+        let (year, jday, hour, min, sec, msec) = t;
+        if jday == 0 || jday > days_in_year(year) || hour > 23 || min > 59 || sec > 59 || msec > 999
+        {
+            let msg = format!("Invalid reference time ({:?})", t);
+            return Err(SacError::custom(msg));
+        }
+
+        self.nzyear = year;
+        self.nzjday = jday as i32;
+        self.nzhour = hour as i32;
+        self.nzmin = min as i32;
+        self.nzsec = sec as i32;
+        self.nzmsec = msec as i32;
+        Ok(())
+    }
+
+    /// The reference date as `(year, month, day)`, or `None` if `nzyear` or
+    /// `nzjday` is undefined or the day is out of range for the year.
+    pub fn calendar_date(&self) -> Option<(i32, u32, u32)> {
+        if self.nzyear == SAC_INT_UNDEF {
+            return None;
+        }
+
+        let mut day = u32::try_from(self.nzjday).ok().filter(|v| *v > 0)?;
+        for (month, length) in month_lengths(self.nzyear).into_iter().enumerate() {
+            if day <= length {
+                return Some((self.nzyear, month as u32 + 1, day));
+            }
+            day -= length;
+        }
+
+        None
+    }
+
+    /// Sets `nzyear` and `nzjday` from a calendar date.
+    pub fn set_calendar_date(&mut self, year: i32, month: u32, day: u32) -> Result<()> {
+        let lengths = month_lengths(year);
+        if !(1..=12).contains(&month) || day == 0 || day > lengths[month as usize - 1] {
+            let msg = format!("Invalid calendar date ({}-{:02}-{:02})", year, month, day);
+            return Err(SacError::custom(msg));
+        }
+
+        self.nzyear = year;
+        self.nzjday = (lengths[..month as usize - 1].iter().sum::<u32>() + day) as i32;
+        Ok(())
+    }
+
+    /// The reference time as a calendar date and time.
+    #[cfg(feature = "chrono")]
+    pub fn reference_datetime(&self) -> Option<chrono::NaiveDateTime> {
+        let (year, jday, hour, min, sec, msec) = self.reference_time()?;
+        chrono::NaiveDate::from_yo_opt(year, jday)?.and_hms_milli_opt(hour, min, sec, msec)
+    }
+
+    // Reference time in seconds since 1970-01-01
+    pub(crate) fn reference_seconds(&self) -> Option<f64> {
+        let fields = [
+            self.nzyear,
+            self.nzjday,
+            self.nzhour,
+            self.nzmin,
+            self.nzsec,
+            self.nzmsec,
+        ];
+        if fields.contains(&SAC_INT_UNDEF) {
+            return None;
+        }
+
+        let days = days_before_year(self.nzyear) + self.nzjday as i64 - 1;
+        let secs =
+            days * 86400 + self.nzhour as i64 * 3600 + self.nzmin as i64 * 60 + self.nzsec as i64;
+
+        Some(secs as f64 + self.nzmsec as f64 / 1000.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl SacHeader {
+    pub fn set_reference_now(&mut self) {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let secs = now.as_secs() as i64;
+        let (year, jday) = year_and_day(secs.div_euclid(86400));
+        let rem = secs.rem_euclid(86400);
+
+        self.nzyear = year;
+        self.nzjday = jday as i32;
+        self.nzhour = (rem / 3600) as i32;
+        self.nzmin = (rem % 3600 / 60) as i32;
+        self.nzsec = (rem % 60) as i32;
+        self.nzmsec = now.subsec_millis() as i32;
+    }
+}
@@ -0,0 +1,35 @@
+/// Returns the smallest length `>= n` whose only prime factors are 2, 3 and 5,
+/// or `None` if there is no such length in `usize`.
+pub fn next_fast_len(n: usize) -> Option<usize> {
+    let target = n.max(1);
+    let mut best: Option<usize> = None;
+
+    // Every candidate is 5^c * 3^b * 2^a; for each odd part take the smallest
+    // power of two that reaches the target
+    let mut p5 = Some(1usize);
+    while let Some(a) = p5 {
+        let mut p35 = Some(a);
+        while let Some(b) = p35 {
+            let mut len = Some(b);
+            while let Some(v) = len.filter(|v| *v < target) {
+                len = v.checked_mul(2);
+            }
+
+            if let Some(v) = len {
+                best = Some(best.map_or(v, |m| m.min(v)));
+            }
+
+            if b >= target {
+                break;
+            }
+            p35 = b.checked_mul(3);
+        }
+
+        if a >= target {
+            break;
+        }
+        p5 = a.checked_mul(5);
+    }
+
+    best
+}
@@ -1,11 +1,26 @@
+use core::fmt;
+
 const ITIME: i32 = 1;
 const IRLIM: i32 = 2;
 const IAMPH: i32 = 3;
 const IXY: i32 = 4;
 const _IXYZ: i32 = 51;
 
+const IUNKN: i32 = 5;
+const IDISP: i32 = 6;
+const IVEL: i32 = 7;
+const IACC: i32 = 8;
+const IVOLTS: i32 = 50;
+
+const IB: i32 = 9;
+const IDAY: i32 = 10;
+const IO: i32 = 11;
+const IA: i32 = 12;
+const IT0: i32 = 13;
+const IT9: i32 = 22;
+
 #[repr(i32)]
-#[derive(PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
 pub enum SacFileType {
     Time = ITIME,
     RealImag = IRLIM,
@@ -14,6 +29,13 @@ pub enum SacFileType {
     Unknown(i32),
 }
 
+impl SacFileType {
+    /// The `iftype` code, including the value carried by `Unknown`.
+    pub fn raw(&self) -> i32 {
+        (*self).into()
+    }
+}
+
 impl From<SacFileType> for i32 {
     fn from(t: SacFileType) -> i32 {
         match t {
@@ -37,3 +59,150 @@ impl From<i32> for SacFileType {
         }
     }
 }
+
+impl fmt::Display for SacFileType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SacFileType::Time => f.write_str("ITIME"),
+            SacFileType::RealImag => f.write_str("IRLIM"),
+            SacFileType::AmpPhase => f.write_str("IAMPH"),
+            SacFileType::XY => f.write_str("IXY"),
+            SacFileType::Unknown(v) => write!(f, "IUNKN({})", v),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum SpectralKind {
+    RealImag,
+    AmpPhase,
+}
+
+#[repr(i32)]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum SacDepType {
+    Unspecified = IUNKN,
+    Displacement = IDISP,
+    Velocity = IVEL,
+    Acceleration = IACC,
+    Volts = IVOLTS,
+    Unknown(i32),
+}
+
+impl SacDepType {
+    /// The `idep` code, including the value carried by `Unknown`.
+    pub fn raw(&self) -> i32 {
+        (*self).into()
+    }
+}
+
+impl From<SacDepType> for i32 {
+    fn from(t: SacDepType) -> i32 {
+        match t {
+            SacDepType::Unspecified => IUNKN,
+            SacDepType::Displacement => IDISP,
+            SacDepType::Velocity => IVEL,
+            SacDepType::Acceleration => IACC,
+            SacDepType::Volts => IVOLTS,
+            SacDepType::Unknown(v) => v,
+        }
+    }
+}
+
+impl From<i32> for SacDepType {
+    fn from(t: i32) -> SacDepType {
+        match t {
+            IUNKN => SacDepType::Unspecified,
+            IDISP => SacDepType::Displacement,
+            IVEL => SacDepType::Velocity,
+            IACC => SacDepType::Acceleration,
+            IVOLTS => SacDepType::Volts,
+            _ => SacDepType::Unknown(t),
+        }
+    }
+}
+
+impl fmt::Display for SacDepType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SacDepType::Unspecified => f.write_str("IUNKN"),
+            SacDepType::Displacement => f.write_str("IDISP"),
+            SacDepType::Velocity => f.write_str("IVEL"),
+            SacDepType::Acceleration => f.write_str("IACC"),
+            SacDepType::Volts => f.write_str("IVOLTS"),
+            SacDepType::Unknown(v) => write!(f, "IUNKN({})", v),
+        }
+    }
+}
+
+/// What the reference time of a file is equivalent to.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum SacZeroType {
+    Unspecified,
+    Begin,
+    Day,
+    Origin,
+    Arrival,
+    /// One of the user picks `t0`..`t9`; the index is 0 to 9. Build it with
+    /// [`SacZeroType::pick`], as larger indices are not valid `iztype` codes
+    /// and read back as `Unknown`.
+    Pick(u8),
+    Unknown(i32),
+}
+
+impl SacZeroType {
+    /// `Pick(i)` for a pick index from 0 to 9, `None` otherwise.
+    pub fn pick(i: usize) -> Option<SacZeroType> {
+        match i {
+            0..=9 => Some(SacZeroType::Pick(i as u8)),
+            _ => None,
+        }
+    }
+
+    /// The `iztype` code, including the value carried by `Unknown`.
+    pub fn raw(&self) -> i32 {
+        (*self).into()
+    }
+}
+
+impl From<SacZeroType> for i32 {
+    fn from(t: SacZeroType) -> i32 {
+        match t {
+            SacZeroType::Unspecified => IUNKN,
+            SacZeroType::Begin => IB,
+            SacZeroType::Day => IDAY,
+            SacZeroType::Origin => IO,
+            SacZeroType::Arrival => IA,
+            SacZeroType::Pick(i) => IT0 + i as i32,
+            SacZeroType::Unknown(v) => v,
+        }
+    }
+}
+
+impl From<i32> for SacZeroType {
+    fn from(t: i32) -> SacZeroType {
+        match t {
+            IUNKN => SacZeroType::Unspecified,
+            IB => SacZeroType::Begin,
+            IDAY => SacZeroType::Day,
+            IO => SacZeroType::Origin,
+            IA => SacZeroType::Arrival,
+            IT0..=IT9 => SacZeroType::Pick((t - IT0) as u8),
+            _ => SacZeroType::Unknown(t),
+        }
+    }
+}
+
+impl fmt::Display for SacZeroType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SacZeroType::Unspecified => f.write_str("IUNKN"),
+            SacZeroType::Begin => f.write_str("IB"),
+            SacZeroType::Day => f.write_str("IDAY"),
+            SacZeroType::Origin => f.write_str("IO"),
+            SacZeroType::Arrival => f.write_str("IA"),
+            SacZeroType::Pick(i) => write!(f, "IT{}", i),
+            SacZeroType::Unknown(v) => write!(f, "IUNKN({})", v),
+        }
+    }
+}
@@ -15,21 +15,37 @@ mod std_error;
 
 pub type Result<T> = result::Result<T, SacError>;
 
-pub struct SacError {
-    msg: String,
+#[derive(Clone)]
+pub enum SacError {
+    TruncatedHeader { expected: usize, found: usize },
+    Io(String),
+    Custom(String),
 }
 
 impl SacError {
     pub(crate) fn custom<T: fmt::Display>(msg: T) -> Self {
-        Self {
-            msg: msg.to_string(),
-        }
+        Self::Custom(msg.to_string())
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn io<T: fmt::Display>(msg: T) -> Self {
+        Self::Io(msg.to_string())
     }
 }
 
 impl fmt::Debug for SacError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(&self.msg)
+        match self {
+            SacError::TruncatedHeader { expected, found } => {
+                write!(
+                    f,
+                    "Truncated header (expected {} bytes, found {})",
+                    expected, found
+                )
+            }
+            SacError::Io(msg) => f.write_str(msg),
+            SacError::Custom(msg) => f.write_str(msg),
+        }
     }
 }
 
@@ -0,0 +1,153 @@
+use alloc::vec::Vec;
+
+use byteorder::{BigEndian as Big, ByteOrder, LittleEndian as Little};
+
+use crate::binary::SAC_FLOAT_UNDEF;
+use crate::header::SacHeader;
+use crate::Endian;
+
+pub(crate) const SAC_FOOTER_VERSION: i32 = 7;
+pub(crate) const SAC_FOOTER_SIZE: usize = 22 * 8;
+
+/// Double-precision copies of the time and coordinate fields, stored after the
+/// data in version 7 files.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SacFooter {
+    pub delta: f64,
+    pub b: f64,
+    pub e: f64,
+    pub o: f64,
+    pub a: f64,
+    pub t: [f64; 10],
+    pub f: f64,
+    pub evlo: f64,
+    pub evla: f64,
+    pub stlo: f64,
+    pub stla: f64,
+    pub sb: f64,
+    pub sdelta: f64,
+}
+
+impl SacFooter {
+    fn to_array(self) -> [f64; 22] {
+        let mut v = [0.0; 22];
+        v[..5].copy_from_slice(&[self.delta, self.b, self.e, self.o, self.a]);
+        v[5..15].copy_from_slice(&self.t);
+        v[15..].copy_from_slice(&[
+            self.f,
+            self.evlo,
+            self.evla,
+            self.stlo,
+            self.stla,
+            self.sb,
+            self.sdelta,
+        ]);
+        v
+    }
+
+    fn from_array(v: [f64; 22]) -> SacFooter {
+        let mut t = [0.0; 10];
+        t.copy_from_slice(&v[5..15]);
+        SacFooter {
+            delta: v[0],
+            b: v[1],
+            e: v[2],
+            o: v[3],
+            a: v[4],
+            t,
+            f: v[15],
+            evlo: v[16],
+            evla: v[17],
+            stlo: v[18],
+            stla: v[19],
+            sb: v[20],
+            sdelta: v[21],
+        }
+    }
+
+    pub(crate) fn bits_eq(&self, other: &SacFooter) -> bool {
+        let (a, b) = (self.to_array(), other.to_array());
+        a.iter().zip(&b).all(|(a, b)| a.to_bits() == b.to_bits())
+    }
+
+    pub(crate) fn bits(&self) -> [u64; 22] {
+        self.to_array().map(f64::to_bits)
+    }
+
+    // The footer as it should be written for `h`: a stored double is kept
+    // while it still rounds to the header value, otherwise the header wins
+    pub(crate) fn reconcile(stored: Option<&SacFooter>, h: &SacHeader) -> SacFooter {
+        let undef = SAC_FLOAT_UNDEF as f64;
+        let stored = stored
+            .copied()
+            .unwrap_or(SacFooter::from_array([undef; 22]));
+        let pick = |d: f64, v: f32| if d as f32 == v { d } else { v as f64 };
+
+        let mut t = [0.0; 10];
+        for (i, v) in t.iter_mut().enumerate() {
+            *v = pick(stored.t[i], h.t[i]);
+        }
+
+        SacFooter {
+            delta: pick(stored.delta, h.delta),
+            b: pick(stored.b, h.b),
+            e: pick(stored.e, h.e),
+            o: pick(stored.o, h.o),
+            a: pick(stored.a, h.a),
+            t,
+            f: pick(stored.f, h.f),
+            evlo: pick(stored.evlo, h.evlo),
+            evla: pick(stored.evla, h.evla),
+            stlo: pick(stored.stlo, h.stlo),
+            stla: pick(stored.stla, h.stla),
+            sb: stored.sb,
+            sdelta: stored.sdelta,
+        }
+    }
+
+    // Copies the doubles onto the single-precision header fields
+    pub(crate) fn apply(&self, h: &mut SacHeader) {
+        h.delta = self.delta as f32;
+        h.b = self.b as f32;
+        h.e = self.e as f32;
+        h.o = self.o as f32;
+        h.a = self.a as f32;
+        for (v, d) in h.t.iter_mut().zip(&self.t) {
+            *v = *d as f32;
+        }
+        h.f = self.f as f32;
+        h.evlo = self.evlo as f32;
+        h.evla = self.evla as f32;
+        h.stlo = self.stlo as f32;
+        h.stla = self.stla as f32;
+    }
+
+    pub(crate) fn decode(src: &[u8], endian: Endian) -> SacFooter {
+        let read_f64 = match endian {
+            Endian::Little => Little::read_f64,
+            Endian::Big => Big::read_f64,
+        };
+
+        let mut v = [0.0; 22];
+        for (v, chunk) in v.iter_mut().zip(src.chunks_exact(8)) {
+            *v = read_f64(chunk);
+        }
+        SacFooter::from_array(v)
+    }
+
+    pub(crate) fn encode(&self, endian: Endian) -> Vec<u8> {
+        let write_f64 = match endian {
+            Endian::Little => Little::write_f64,
+            Endian::Big => Big::write_f64,
+        };
+
+        self.to_array()
+            .iter()
+            .flat_map(|v| {
+                let mut byte = [0; 8];
+                write_f64(&mut byte, *v);
+                byte
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,38 @@
+use core::f64::consts::PI;
+
+use crate::binary::SAC_FLOAT_UNDEF;
+use crate::header::SacHeader;
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+// Azimuth in degrees [0, 360) from point 1 to point 2, all in radians
+#[inline]
+fn azimuth(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let dlon = lon2 - lon1;
+    let y = libm::sin(dlon) * libm::cos(lat2);
+    let x = libm::cos(lat1) * libm::sin(lat2) - libm::sin(lat1) * libm::cos(lat2) * libm::cos(dlon);
+    (libm::atan2(y, x).to_degrees() + 360.0) % 360.0
+}
+
+impl SacHeader {
+    /// Fills `gcarc`, `az`, `baz` (degrees) and `dist` (km) from the event and
+    /// station coordinates on a spherical Earth. Nothing changes unless
+    /// `lcalda` is set and all four coordinates are defined.
+    pub fn compute_dist_az(&mut self) {
+        let coords = [self.evla, self.evlo, self.stla, self.stlo];
+        if !self.lcalda || coords.contains(&SAC_FLOAT_UNDEF) {
+            return;
+        }
+
+        let [evla, evlo, stla, stlo] = coords.map(|v| v as f64 * PI / 180.0);
+
+        let h = libm::pow(libm::sin((stla - evla) / 2.0), 2.0)
+            + libm::cos(evla) * libm::cos(stla) * libm::pow(libm::sin((stlo - evlo) / 2.0), 2.0);
+        let arc = 2.0 * libm::asin(libm::sqrt(h.min(1.0)));
+
+        self.gcarc = arc.to_degrees() as f32;
+        self.dist = (arc * EARTH_RADIUS_KM) as f32;
+        self.az = azimuth(evla, evlo, stla, stlo) as f32;
+        self.baz = azimuth(stla, stlo, evla, evlo) as f32;
+    }
+}
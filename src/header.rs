@@ -1,8 +1,12 @@
-use alloc::string::String;
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::hash::{Hash, Hasher};
 
-use crate::binary::SacBinary;
-use crate::enums::SacFileType;
+use crate::binary::{SacBinary, SAC_FLOAT_UNDEF};
+use crate::enums::{SacDepType, SacFileType, SacZeroType};
+use crate::error::{Result, SacError};
 
+#[derive(Debug, Clone)]
 pub struct SacHeader {
     pub delta: f32,
     pub depmin: f32,
@@ -51,8 +55,8 @@ pub struct SacHeader {
     pub nxsize: i32,
     pub nysize: i32,
     pub iftype: SacFileType,
-    pub idep: i32,
-    pub iztype: i32,
+    pub idep: SacDepType,
+    pub iztype: SacZeroType,
     pub iinst: i32,
     pub istreg: i32,
     pub ievreg: i32,
@@ -86,4 +90,308 @@ impl SacHeader {
         let b = SacBinary::default();
         SacHeader::from(&b)
     }
+
+    pub fn sampling_rate(&self) -> Option<f32> {
+        if self.delta == SAC_FLOAT_UNDEF || self.delta <= 0.0 || !self.delta.is_finite() {
+            return None;
+        }
+
+        Some(1.0 / self.delta)
+    }
+
+    /// Sampling rate rounded to `decimals` places, for display only.
+    pub fn sample_rate_rounded(&self, decimals: u32) -> Option<f32> {
+        let rate = self.sampling_rate()? as f64;
+        let factor = libm::pow(10.0, decimals as f64);
+        Some((libm::round(rate * factor) / factor) as f32)
+    }
+
+    pub fn pick(&self, i: usize) -> Option<(f32, &str)> {
+        let time = *self.t.get(i)?;
+        if time == SAC_FLOAT_UNDEF {
+            return None;
+        }
+
+        let label = match self.kt[i].as_str() {
+            "-12345" => "",
+            v => v,
+        };
+
+        Some((time, label))
+    }
+
+    /// Splits a SEED channel name in `kcmpnm` (e.g. `BHZ`) into its band,
+    /// instrument and orientation codes.
+    pub fn channel_code(&self) -> Option<(char, char, char)> {
+        let mut chars = self.kcmpnm.trim_end().chars();
+        let code = (chars.next()?, chars.next()?, chars.next()?);
+        if chars.next().is_some() {
+            return None;
+        }
+
+        let (band, instrument, orientation) = code;
+        if band.is_ascii_uppercase()
+            && instrument.is_ascii_uppercase()
+            && (orientation.is_ascii_uppercase() || orientation.is_ascii_digit())
+        {
+            Some(code)
+        } else {
+            None
+        }
+    }
+
+    /// Sets pick `t{i}` and its label `kt{i}`; `i` is 0 to 9.
+    pub fn set_pick(&mut self, i: usize, time: f32, label: &str) -> Result<()> {
+        if i >= self.t.len() {
+            let msg = format!("Invalid pick index ({})", i);
+            return Err(SacError::custom(msg));
+        }
+
+        self.t[i] = time;
+        self.kt[i] = label.to_string();
+        Ok(())
+    }
+
+    /// Stable 64-bit FNV-1a hash of the header fields, using the same
+    /// bit-pattern rules as the `Hash` impl. The data blocks are not included.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = Fnv1a(0xcbf29ce484222325);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn validate_ascii(&self) -> Result<()> {
+        let fields = [
+            ("kstnm", &self.kstnm),
+            ("kevnm", &self.kevnm),
+            ("khole", &self.khole),
+            ("ko", &self.ko),
+            ("ka", &self.ka),
+            ("kf", &self.kf),
+            ("kuser0", &self.kuser0),
+            ("kuser1", &self.kuser1),
+            ("kuser2", &self.kuser2),
+            ("kcmpnm", &self.kcmpnm),
+            ("knetwk", &self.knetwk),
+            ("kdatrd", &self.kdatrd),
+            ("kinst", &self.kinst),
+        ];
+        let kt = self.kt.iter().map(|v| ("kt", v));
+
+        for (name, value) in fields.into_iter().chain(kt) {
+            if !value.is_ascii() {
+                let msg = format!("Non-ASCII header string ({} = {})", name, value);
+                return Err(SacError::custom(msg));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for SacHeader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Float fields are hashed and compared by their bit patterns, so `NaN` equals
+/// itself and `0.0` differs from `-0.0`.
+impl Hash for SacHeader {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u32(self.delta.to_bits());
+        state.write_u32(self.depmin.to_bits());
+        state.write_u32(self.depmax.to_bits());
+        state.write_u32(self.scale.to_bits());
+        state.write_u32(self.odelta.to_bits());
+        state.write_u32(self.b.to_bits());
+        state.write_u32(self.e.to_bits());
+        state.write_u32(self.o.to_bits());
+        state.write_u32(self.a.to_bits());
+        self.t.iter().for_each(|v| state.write_u32(v.to_bits()));
+        state.write_u32(self.f.to_bits());
+        self.resp.iter().for_each(|v| state.write_u32(v.to_bits()));
+        state.write_u32(self.stla.to_bits());
+        state.write_u32(self.stlo.to_bits());
+        state.write_u32(self.stel.to_bits());
+        state.write_u32(self.stdp.to_bits());
+        state.write_u32(self.evla.to_bits());
+        state.write_u32(self.evlo.to_bits());
+        state.write_u32(self.evel.to_bits());
+        state.write_u32(self.evdp.to_bits());
+        state.write_u32(self.mag.to_bits());
+        self.user.iter().for_each(|v| state.write_u32(v.to_bits()));
+        state.write_u32(self.dist.to_bits());
+        state.write_u32(self.az.to_bits());
+        state.write_u32(self.baz.to_bits());
+        state.write_u32(self.gcarc.to_bits());
+        state.write_u32(self.depmen.to_bits());
+        state.write_u32(self.cmpaz.to_bits());
+        state.write_u32(self.cmpinc.to_bits());
+        state.write_u32(self.xminimum.to_bits());
+        state.write_u32(self.xmaximum.to_bits());
+        state.write_u32(self.yminimum.to_bits());
+        state.write_u32(self.ymaximum.to_bits());
+        self.nzyear.hash(state);
+        self.nzjday.hash(state);
+        self.nzhour.hash(state);
+        self.nzmin.hash(state);
+        self.nzsec.hash(state);
+        self.nzmsec.hash(state);
+        self.nvhdr.hash(state);
+        self.norid.hash(state);
+        self.nevid.hash(state);
+        self.npts.hash(state);
+        self.nwfid.hash(state);
+        self.nxsize.hash(state);
+        self.nysize.hash(state);
+        self.iftype.hash(state);
+        self.idep.hash(state);
+        self.iztype.hash(state);
+        self.iinst.hash(state);
+        self.istreg.hash(state);
+        self.ievreg.hash(state);
+        self.ievtyp.hash(state);
+        self.iqual.hash(state);
+        self.isynth.hash(state);
+        self.imagtyp.hash(state);
+        self.imagsrc.hash(state);
+        self.leven.hash(state);
+        self.lpspol.hash(state);
+        self.lovrok.hash(state);
+        self.lcalda.hash(state);
+        self.kstnm.hash(state);
+        self.kevnm.hash(state);
+        self.khole.hash(state);
+        self.ko.hash(state);
+        self.ka.hash(state);
+        self.kt.hash(state);
+        self.kf.hash(state);
+        self.kuser0.hash(state);
+        self.kuser1.hash(state);
+        self.kuser2.hash(state);
+        self.kcmpnm.hash(state);
+        self.knetwk.hash(state);
+        self.kdatrd.hash(state);
+        self.kinst.hash(state);
+    }
+}
+
+impl PartialEq for SacHeader {
+    fn eq(&self, other: &Self) -> bool {
+        self.delta.to_bits() == other.delta.to_bits()
+            && self.depmin.to_bits() == other.depmin.to_bits()
+            && self.depmax.to_bits() == other.depmax.to_bits()
+            && self.scale.to_bits() == other.scale.to_bits()
+            && self.odelta.to_bits() == other.odelta.to_bits()
+            && self.b.to_bits() == other.b.to_bits()
+            && self.e.to_bits() == other.e.to_bits()
+            && self.o.to_bits() == other.o.to_bits()
+            && self.a.to_bits() == other.a.to_bits()
+            && bits_eq(&self.t, &other.t)
+            && self.f.to_bits() == other.f.to_bits()
+            && bits_eq(&self.resp, &other.resp)
+            && self.stla.to_bits() == other.stla.to_bits()
+            && self.stlo.to_bits() == other.stlo.to_bits()
+            && self.stel.to_bits() == other.stel.to_bits()
+            && self.stdp.to_bits() == other.stdp.to_bits()
+            && self.evla.to_bits() == other.evla.to_bits()
+            && self.evlo.to_bits() == other.evlo.to_bits()
+            && self.evel.to_bits() == other.evel.to_bits()
+            && self.evdp.to_bits() == other.evdp.to_bits()
+            && self.mag.to_bits() == other.mag.to_bits()
+            && bits_eq(&self.user, &other.user)
+            && self.dist.to_bits() == other.dist.to_bits()
+            && self.az.to_bits() == other.az.to_bits()
+            && self.baz.to_bits() == other.baz.to_bits()
+            && self.gcarc.to_bits() == other.gcarc.to_bits()
+            && self.depmen.to_bits() == other.depmen.to_bits()
+            && self.cmpaz.to_bits() == other.cmpaz.to_bits()
+            && self.cmpinc.to_bits() == other.cmpinc.to_bits()
+            && self.xminimum.to_bits() == other.xminimum.to_bits()
+            && self.xmaximum.to_bits() == other.xmaximum.to_bits()
+            && self.yminimum.to_bits() == other.yminimum.to_bits()
+            && self.ymaximum.to_bits() == other.ymaximum.to_bits()
+            && self.nzyear == other.nzyear
+            && self.nzjday == other.nzjday
+            && self.nzhour == other.nzhour
+            && self.nzmin == other.nzmin
+            && self.nzsec == other.nzsec
+            && self.nzmsec == other.nzmsec
+            && self.nvhdr == other.nvhdr
+            && self.norid == other.norid
+            && self.nevid == other.nevid
+            && self.npts == other.npts
+            && self.nwfid == other.nwfid
+            && self.nxsize == other.nxsize
+            && self.nysize == other.nysize
+            && self.iftype == other.iftype
+            && self.idep == other.idep
+            && self.iztype == other.iztype
+            && self.iinst == other.iinst
+            && self.istreg == other.istreg
+            && self.ievreg == other.ievreg
+            && self.ievtyp == other.ievtyp
+            && self.iqual == other.iqual
+            && self.isynth == other.isynth
+            && self.imagtyp == other.imagtyp
+            && self.imagsrc == other.imagsrc
+            && self.leven == other.leven
+            && self.lpspol == other.lpspol
+            && self.lovrok == other.lovrok
+            && self.lcalda == other.lcalda
+            && self.kstnm == other.kstnm
+            && self.kevnm == other.kevnm
+            && self.khole == other.khole
+            && self.ko == other.ko
+            && self.ka == other.ka
+            && self.kt == other.kt
+            && self.kf == other.kf
+            && self.kuser0 == other.kuser0
+            && self.kuser1 == other.kuser1
+            && self.kuser2 == other.kuser2
+            && self.kcmpnm == other.kcmpnm
+            && self.knetwk == other.knetwk
+            && self.kdatrd == other.kdatrd
+            && self.kinst == other.kinst
+    }
+}
+
+impl Eq for SacHeader {}
+
+#[inline]
+pub(crate) fn bits_eq(a: &[f32], b: &[f32]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.to_bits() == y.to_bits())
+}
+
+// Integers are fed in little-endian order so the hash is the same on every platform
+struct Fnv1a(u64);
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= *b as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
 }
@@ -13,18 +13,31 @@ use bincode::{decode_from_slice, encode_into_slice};
 use byteorder::{BigEndian as Big, ByteOrder, LittleEndian as Little};
 
 use crate::binary::SacBinary;
-pub use crate::enums::SacFileType;
+pub use crate::date::RefTime;
+pub use crate::enums::{SacDepType, SacFileType, SacZeroType, SpectralKind};
 use crate::error::SacError;
+pub use crate::footer::SacFooter;
+use crate::footer::{SAC_FOOTER_SIZE, SAC_FOOTER_VERSION};
 pub use crate::header::SacHeader;
-pub use crate::sac::Sac;
+pub use crate::ops::{Junction, MergeReport, TaperPlan, TaperWindow, TraceStats};
+pub use crate::sac::{Components, Sac};
+pub use crate::validate::{Severity, ValidationIssue};
+pub use crate::waveform::{SacSliceExt, Waveform};
 
 mod binary;
+mod date;
+pub mod dsp;
 mod enums;
 pub mod error;
+mod footer;
+mod geo;
 mod header;
+mod ops;
 mod sac;
+mod validate;
+mod waveform;
 
-#[derive(Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum Endian {
     Little,
     Big,
@@ -32,6 +45,8 @@ pub enum Endian {
 
 const SAC_HEADER_SIZE: usize = 632;
 const SAC_HEADER_MAJOR_VERSION: i32 = 6;
+const SAC_HEADER_VERSIONS: [i32; 2] = [6, 7];
+const SAC_NVHDR_OFFSET: usize = 304;
 
 const LITTLE_ENDIAN_CONFIG: Configuration<LittleEndian, Fixint> = bincode::config::standard()
     .with_little_endian()
@@ -66,29 +81,28 @@ impl SacBinary {
             Endian::Big => Big::read_f32,
         };
 
-        src.chunks_exact(4).map(|b| read_f32(b)).collect()
+        src.chunks_exact(4).map(read_f32).collect()
     }
 
     #[inline]
-    fn encode_data(val: &Vec<f32>, endian: Endian) -> Vec<u8> {
+    fn encode_data(val: &[f32], dst: &mut Vec<u8>, endian: Endian) {
         let write_f32 = match endian {
             Endian::Little => Little::write_f32,
             Endian::Big => Big::write_f32,
         };
 
-        val.iter()
-            .flat_map(|v| {
-                let mut byte = [0; 4];
-                write_f32(&mut byte, *v);
-                byte
-            })
-            .collect()
+        dst.reserve(val.len() * 4);
+        for v in val {
+            let mut byte = [0; 4];
+            write_f32(&mut byte, *v);
+            dst.extend_from_slice(&byte);
+        }
     }
 }
 
 macro_rules! check_header {
     ($self:ident) => {
-        if $self.nvhdr != SAC_HEADER_MAJOR_VERSION {
+        if !SAC_HEADER_VERSIONS.contains(&$self.nvhdr) {
             let msg = format!("Unsupported major version (nvhdr = {})", $self.nvhdr);
             return Err(SacError::custom(msg));
         }
@@ -108,107 +122,533 @@ impl Sac {
         self.h = h
     }
 
-    pub unsafe fn from_slice_unchecked(src: &[u8], endian: Endian) -> error::Result<Sac> {
-        let mut h_src = Vec::new();
-        let mut d_src = Vec::new();
+    /// Guesses the byte order of a SAC file from its header version, which is
+    /// only a small number (6 or 7) when read the right way round.
+    pub fn detect_endian(src: &[u8]) -> error::Result<Endian> {
+        if src.len() < SAC_HEADER_SIZE {
+            return Err(SacError::TruncatedHeader {
+                expected: SAC_HEADER_SIZE,
+                found: src.len(),
+            });
+        }
 
-        if src.len() > SAC_HEADER_SIZE {
-            h_src.extend_from_slice(&src[..SAC_HEADER_SIZE]);
-            d_src.extend_from_slice(&src[SAC_HEADER_SIZE..]);
+        let word = &src[SAC_NVHDR_OFFSET..SAC_NVHDR_OFFSET + 4];
+        let little = Little::read_i32(word);
+        let big = Big::read_i32(word);
+        if SAC_HEADER_VERSIONS.contains(&little) {
+            Ok(Endian::Little)
+        } else if SAC_HEADER_VERSIONS.contains(&big) {
+            Ok(Endian::Big)
         } else {
-            h_src.extend_from_slice(src);
-        };
+            let msg = format!(
+                "Unable to detect byte order (nvhdr = {} as little endian, {} as big endian)",
+                little, big
+            );
+            Err(SacError::custom(msg))
+        }
+    }
+
+    /// Like [`Sac::from_slice`], with the byte order chosen by
+    /// [`Sac::detect_endian`] and returned alongside the file.
+    pub fn from_slice_auto(src: &[u8]) -> error::Result<(Sac, Endian)> {
+        let endian = Self::detect_endian(src)?;
+        Ok((Self::from_slice(src, endian)?, endian))
+    }
+
+    // Number of 4-byte values in the data section for `size` points
+    fn data_count(&self, size: usize) -> usize {
+        if self.iftype == SacFileType::Time && self.leven {
+            size
+        } else {
+            size.saturating_mul(2)
+        }
+    }
+
+    fn decode_counted(src: &[u8], endian: Endian) -> error::Result<(Sac, usize)> {
+        if src.len() < SAC_HEADER_SIZE {
+            return Err(SacError::TruncatedHeader {
+                expected: SAC_HEADER_SIZE,
+                found: src.len(),
+            });
+        }
 
-        let binary = match SacBinary::decode_header(&h_src, endian) {
+        let (h_src, d_src) = src.split_at(SAC_HEADER_SIZE);
+        let binary = match SacBinary::decode_header(h_src, endian) {
             Ok(b) => b,
             Err(err) => return Err(SacError::custom(err)),
         };
 
         let mut sac = Sac::build(&binary);
 
-        let data = SacBinary::decode_data(&d_src, endian);
-        if sac.iftype == SacFileType::Time && sac.leven {
-            sac.first = data;
-            return Ok(sac);
+        // Header-only files are valid and leave the data empty, whatever npts says
+        if d_src.is_empty() {
+            return Ok((sac, SAC_HEADER_SIZE));
         }
 
-        let size = usize::try_from(sac.npts).unwrap_or(data.len());
-        if size > data.len() {
-            sac.first = data
+        let available = d_src.len() / 4;
+        let size = usize::try_from(sac.npts).unwrap_or(available);
+        let count = sac.data_count(size);
+
+        // Version 7 keeps double-precision copies of some fields after the data
+        let mut footer_size = 0;
+        if sac.nvhdr == SAC_FOOTER_VERSION && count < available {
+            let f_src = &d_src[count * 4..];
+            if f_src.len() >= SAC_FOOTER_SIZE {
+                let footer = SacFooter::decode(&f_src[..SAC_FOOTER_SIZE], endian);
+                footer.apply(&mut sac.h);
+                sac.footer = Some(footer);
+                footer_size = SAC_FOOTER_SIZE;
+            }
+        }
+
+        let count = count.min(available);
+        let data = SacBinary::decode_data(&d_src[..count * 4], endian);
+        if size >= data.len() {
+            sac.first = data;
         } else {
             sac.first = data[..size].to_vec();
             sac.second = data[size..].to_vec();
         }
 
-        Ok(sac)
+        Ok((sac, SAC_HEADER_SIZE + count * 4 + footer_size))
+    }
+
+    /// # Safety
+    ///
+    /// The header is not validated, so `nvhdr` and `iftype` may hold unsupported values.
+    pub unsafe fn from_slice_unchecked(src: &[u8], endian: Endian) -> error::Result<Sac> {
+        Self::decode_counted(src, endian).map(|(sac, _)| sac)
     }
 
     pub fn from_slice(src: &[u8], endian: Endian) -> error::Result<Sac> {
-        let sac = unsafe { Self::from_slice_unchecked(src, endian) }?;
-        check_header!(sac);
+        let (sac, count) = Self::from_slice_counted(src, endian)?;
+        if count != src.len() {
+            let msg = format!("Unexpected trailing data ({} bytes)", src.len() - count);
+            return Err(SacError::custom(msg));
+        }
+
         Ok(sac)
     }
 
+    /// Decodes a SAC file and returns it with the number of bytes it used, so
+    /// further data may follow. The data section must hold `npts` samples for
+    /// evenly spaced time series and `2 * npts` otherwise; a header without
+    /// any data is accepted too.
+    pub fn from_slice_counted(src: &[u8], endian: Endian) -> error::Result<(Sac, usize)> {
+        let (sac, count) = Self::decode_counted(src, endian)?;
+        check_header!(sac);
+
+        if src.len() > SAC_HEADER_SIZE {
+            let size = usize::try_from(sac.npts).unwrap_or(usize::MAX);
+            let expected = if sac.iftype == SacFileType::Time && sac.leven {
+                size
+            } else {
+                size.saturating_mul(2)
+            };
+
+            let found = sac.first.len() + sac.second.len();
+            if found != expected {
+                let msg = format!(
+                    "Data length mismatch (iftype = {}, npts = {}, expected {} samples, found {})",
+                    sac.iftype, sac.npts, expected, found
+                );
+                return Err(SacError::custom(msg));
+            }
+        }
+
+        Ok((sac, count))
+    }
+
+    /// Like [`Sac::to_slice_raw`], but without validating the header.
+    ///
+    /// # Safety
+    ///
+    /// The header is not validated, so the output may not be a readable SAC file.
     pub unsafe fn to_slice_unchecked(&self, endian: Endian) -> error::Result<Vec<u8>> {
+        let mut val = Vec::new();
+        self.encode_into(&mut val, endian, false, self.nvhdr)?;
+        Ok(val)
+    }
+
+    // Encodes with the header refreshed from the data if `refresh` is set,
+    // writing version `nvhdr`
+    fn encode_into(
+        &self,
+        dst: &mut Vec<u8>,
+        endian: Endian,
+        refresh: bool,
+        nvhdr: i32,
+    ) -> error::Result<()> {
         let mut h_val = [0; SAC_HEADER_SIZE];
 
-        let header = SacBinary::from(self);
-        match SacBinary::encode_header(header, &mut h_val, endian) {
+        let mut h = self.h.clone();
+        h.nvhdr = nvhdr;
+        if refresh {
+            (h.npts, h.e) = self.synced_lengths()?;
+            if let Some(stats) = self.stats() {
+                h.depmin = stats.min;
+                h.depmax = stats.max;
+                h.depmen = stats.mean;
+            }
+        }
+
+        match SacBinary::encode_header(SacBinary::from(&h), &mut h_val, endian) {
             Ok(v) => v,
             Err(err) => return Err(SacError::custom(err)),
         };
 
-        let mut data = self.first.clone();
-        data.extend_from_slice(&self.second);
-        let d_val = SacBinary::encode_data(&data, endian);
+        dst.clear();
+        dst.extend_from_slice(&h_val);
+        SacBinary::encode_data(&self.first, dst, endian);
+        SacBinary::encode_data(&self.second, dst, endian);
+        // The footer must agree with the refreshed header, or reading it back
+        // would restore the stale values
+        if h.nvhdr == SAC_FOOTER_VERSION {
+            let footer = SacFooter::reconcile(self.footer.as_ref(), &h);
+            dst.extend_from_slice(&footer.encode(endian));
+        }
 
-        let mut val = h_val.to_vec();
-        val.extend_from_slice(&d_val);
+        Ok(())
+    }
 
+    /// Encodes the file with `npts`, `e`, `depmin`, `depmax` and `depmen`
+    /// computed from the data, as [`Sac::sync_lengths`] and
+    /// [`Sac::update_extrema`] would set them.
+    pub fn to_slice(&self, endian: Endian) -> error::Result<Vec<u8>> {
+        let mut val = Vec::new();
+        self.write_into(&mut val, endian)?;
         Ok(val)
     }
 
-    pub fn to_slice(&self, endian: Endian) -> error::Result<Vec<u8>> {
+    /// Like [`Sac::to_slice`], but replaces the contents of `buf`, reusing its
+    /// allocation.
+    pub fn write_into(&self, buf: &mut Vec<u8>, endian: Endian) -> error::Result<()> {
+        check_header!(self);
+        self.encode_into(buf, endian, true, self.nvhdr)
+    }
+
+    /// Like [`Sac::to_slice`], but writes header version `version` whatever
+    /// `nvhdr` says. Version 7 writes the double-precision footer; version 6
+    /// drops it, so the times and coordinates it held keep only the single
+    /// precision of the header. Other versions fail.
+    pub fn to_slice_version(&self, endian: Endian, version: i32) -> error::Result<Vec<u8>> {
+        check_header!(self);
+        if !SAC_HEADER_VERSIONS.contains(&version) {
+            let msg = format!("Unsupported major version (nvhdr = {})", version);
+            return Err(SacError::custom(msg));
+        }
+
+        let mut val = Vec::new();
+        self.encode_into(&mut val, endian, true, version)?;
+        Ok(val)
+    }
+
+    /// Encodes the header exactly as it is, without refreshing the lengths or
+    /// the extrema.
+    pub fn to_slice_raw(&self, endian: Endian) -> error::Result<Vec<u8>> {
         check_header!(self);
-        unsafe { self.to_slice_unchecked(endian) }
+        let mut val = Vec::new();
+        self.encode_into(&mut val, endian, false, self.nvhdr)?;
+        Ok(val)
     }
 }
 
 #[cfg(feature = "std")]
 impl Sac {
-    pub fn from_file(path: &Path, endian: Endian) -> error::Result<Sac> {
-        use std::fs::File;
-        use std::io::Read;
+    pub fn from_file(path: impl AsRef<Path>, endian: Endian) -> error::Result<Sac> {
+        use std::io::BufReader;
 
-        let mut f = match File::open(path) {
-            Ok(f) => f,
-            Err(err) => return Err(SacError::custom(err)),
-        };
+        let f = open_file(path.as_ref())?;
+        Self::from_reader(&mut BufReader::new(f), endian)
+    }
 
-        let mut src = Vec::new();
-        match f.read_to_end(&mut src) {
-            Ok(v) => v,
-            Err(err) => return Err(SacError::custom(err)),
+    /// Like [`Sac::from_file`], with the byte order chosen by
+    /// [`Sac::detect_endian`] and returned alongside the file.
+    pub fn from_file_auto(path: impl AsRef<Path>) -> error::Result<(Sac, Endian)> {
+        let src = read_file(path.as_ref())?;
+        Self::from_slice_auto(&src)
+    }
+
+    /// Reads `reader` to the end and decodes it like [`Sac::from_slice`]. A
+    /// stream shorter than the header fails with [`SacError::TruncatedHeader`].
+    pub fn from_reader<R: std::io::Read>(reader: &mut R, endian: Endian) -> error::Result<Sac> {
+        let src = read_all(reader)?;
+        Self::from_slice(&src, endian)
+    }
+
+    /// Reads a whole SAC file from `r`, calling `cb` with the number of bytes
+    /// read so far and the total size (`None`, since a reader does not know
+    /// its length) after every chunk. [`Sac::from_file_progress`] reports the
+    /// size of a file.
+    pub fn from_reader_progress<R: std::io::Read>(
+        r: &mut R,
+        endian: Endian,
+        mut cb: impl FnMut(u64, Option<u64>),
+    ) -> error::Result<Sac> {
+        let src = read_chunks(r, |n| {
+            cb(n as u64, None);
+            Ok(())
+        })?;
+
+        Self::from_slice(&src, endian)
+    }
+
+    /// Like [`Sac::from_reader_progress`] for the file at `path`, reporting its
+    /// size as the total.
+    pub fn from_file_progress(
+        path: impl AsRef<Path>,
+        endian: Endian,
+        mut cb: impl FnMut(u64, Option<u64>),
+    ) -> error::Result<Sac> {
+        use std::io::BufReader;
+
+        let f = open_file(path.as_ref())?;
+        let total = match f.metadata() {
+            Ok(m) => m.len(),
+            Err(err) => return Err(SacError::io(err)),
         };
 
+        let src = read_chunks(&mut BufReader::new(f), |n| {
+            cb(n as u64, Some(total));
+            Ok(())
+        })?;
+
         Self::from_slice(&src, endian)
     }
 
-    pub fn to_file(&self, path: &Path, endian: Endian) -> error::Result<()> {
-        use std::fs::File;
-        use std::io::Write;
+    /// Reads a whole SAC file from `r`, failing with `SacError::Io("read timed
+    /// out")` once `deadline` has passed. The clock is checked between reads,
+    /// so a single blocking read is not interrupted.
+    pub fn from_reader_deadline<R: std::io::Read>(
+        r: &mut R,
+        endian: Endian,
+        deadline: std::time::Instant,
+    ) -> error::Result<Sac> {
+        let src = read_chunks(r, |_| {
+            if std::time::Instant::now() > deadline {
+                return Err(SacError::Io("read timed out".into()));
+            }
+
+            Ok(())
+        })?;
 
-        let mut f = match File::create(path) {
+        Self::from_slice(&src, endian)
+    }
+
+    pub fn read_header_from<R: std::io::Read>(r: &mut R, endian: Endian) -> error::Result<Sac> {
+        let mut src = [0; SAC_HEADER_SIZE];
+        match r.read_exact(&mut src) {
             Ok(v) => v,
+            Err(err) => return Err(SacError::io(err)),
+        };
+
+        let binary = match SacBinary::decode_header(&src, endian) {
+            Ok(b) => b,
             Err(err) => return Err(SacError::custom(err)),
         };
 
+        let sac = Sac::build(&binary);
+        check_header!(sac);
+        Ok(sac)
+    }
+
+    /// Encodes like [`Sac::to_slice`], writes the bytes to `writer` and
+    /// flushes it.
+    pub fn to_writer<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        endian: Endian,
+    ) -> error::Result<()> {
+        let val = self.to_slice(endian)?;
+        write_to(writer, &val)
+    }
+
+    pub fn to_file(&self, path: impl AsRef<Path>, endian: Endian) -> error::Result<()> {
         let val = self.to_slice(endian)?;
-        match f.write_all(&val) {
+        write_file(path.as_ref(), &val, false)
+    }
+
+    /// Like [`Sac::to_file`], but waits until the file has reached the disk
+    /// (`File::sync_all`) before returning.
+    pub fn to_file_synced(&self, path: impl AsRef<Path>, endian: Endian) -> error::Result<()> {
+        let val = self.to_slice(endian)?;
+        write_file(path.as_ref(), &val, true)
+    }
+
+    /// Like [`Sac::to_file`], but writes the header as [`Sac::to_slice_raw`]
+    /// does.
+    pub fn to_file_raw(&self, path: impl AsRef<Path>, endian: Endian) -> error::Result<()> {
+        let val = self.to_slice_raw(endian)?;
+        write_file(path.as_ref(), &val, false)
+    }
+
+    /// Like [`Sac::to_file`], but writes header version `version` as
+    /// [`Sac::to_slice_version`] does.
+    pub fn to_file_version(
+        &self,
+        path: impl AsRef<Path>,
+        endian: Endian,
+        version: i32,
+    ) -> error::Result<()> {
+        let val = self.to_slice_version(endian, version)?;
+        write_file(path.as_ref(), &val, false)
+    }
+
+    pub fn to_file_mkdir(&self, path: impl AsRef<Path>, endian: Endian) -> error::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            match std::fs::create_dir_all(parent) {
+                Ok(v) => v,
+                Err(err) => return Err(SacError::io(err)),
+            };
+        }
+
+        self.to_file(path, endian)
+    }
+
+    pub fn convert_endian_file(
+        src: impl AsRef<Path>,
+        dst: impl AsRef<Path>,
+        from: Endian,
+        to: Endian,
+    ) -> error::Result<()> {
+        let src = read_file(src.as_ref())?;
+
+        if src.len() < SAC_HEADER_SIZE {
+            return Err(SacError::TruncatedHeader {
+                expected: SAC_HEADER_SIZE,
+                found: src.len(),
+            });
+        }
+
+        let binary = match SacBinary::decode_header(&src[..SAC_HEADER_SIZE], from) {
+            Ok(b) => b,
+            Err(err) => return Err(SacError::custom(err)),
+        };
+
+        let sac = Sac::build(&binary);
+        check_header!(sac);
+
+        let mut val = [0; SAC_HEADER_SIZE].to_vec();
+        match SacBinary::encode_header(binary, &mut val, to) {
             Ok(v) => v,
             Err(err) => return Err(SacError::custom(err)),
         };
 
-        Ok(())
+        // Only the bytes after the data can be a footer, as in `from_slice`
+        let mut d_src = &src[SAC_HEADER_SIZE..];
+        let mut f_src: &[u8] = &[];
+        let available = d_src.len() / 4;
+        let size = usize::try_from(sac.npts).unwrap_or(available);
+        let count = sac.data_count(size);
+        if sac.nvhdr == SAC_FOOTER_VERSION
+            && count < available
+            && d_src.len() - count * 4 >= SAC_FOOTER_SIZE
+        {
+            (d_src, f_src) = d_src.split_at(count * 4);
+        }
+
+        let data = SacBinary::decode_data(d_src, from);
+        SacBinary::encode_data(&data, &mut val, to);
+        if !f_src.is_empty() {
+            let (f_src, rest) = f_src.split_at(SAC_FOOTER_SIZE);
+            val.extend_from_slice(&SacFooter::decode(f_src, from).encode(to));
+            let rest = SacBinary::decode_data(rest, from);
+            SacBinary::encode_data(&rest, &mut val, to);
+        }
+
+        write_file(dst.as_ref(), &val, false)
     }
 }
+
+#[cfg(feature = "std")]
+fn write_to<W: std::io::Write>(writer: &mut W, val: &[u8]) -> error::Result<()> {
+    match writer.write_all(val) {
+        Ok(v) => v,
+        Err(err) => return Err(SacError::io(err)),
+    };
+
+    match writer.flush() {
+        Ok(v) => v,
+        Err(err) => return Err(SacError::io(err)),
+    };
+
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+fn write_file(path: &Path, val: &[u8], sync: bool) -> error::Result<()> {
+    use std::fs::File;
+
+    let mut f = match File::create(path) {
+        Ok(v) => v,
+        Err(err) => return Err(SacError::io(err)),
+    };
+
+    write_to(&mut f, val)?;
+
+    if sync {
+        match f.sync_all() {
+            Ok(v) => v,
+            Err(err) => return Err(SacError::io(err)),
+        };
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+fn open_file(path: &Path) -> error::Result<std::fs::File> {
+    if path.is_dir() {
+        let msg = format!("{}: path is a directory", path.display());
+        return Err(SacError::Io(msg));
+    }
+
+    match std::fs::File::open(path) {
+        Ok(f) => Ok(f),
+        Err(err) => Err(SacError::io(err)),
+    }
+}
+
+#[cfg(feature = "std")]
+fn read_file(path: &Path) -> error::Result<Vec<u8>> {
+    use std::io::BufReader;
+
+    let f = open_file(path)?;
+    read_all(&mut BufReader::new(f))
+}
+
+#[cfg(feature = "std")]
+fn read_all<R: std::io::Read>(reader: &mut R) -> error::Result<Vec<u8>> {
+    let mut src = Vec::new();
+    match reader.read_to_end(&mut src) {
+        Ok(v) => v,
+        Err(err) => return Err(SacError::io(err)),
+    };
+
+    Ok(src)
+}
+
+// Reads `r` to the end in chunks, calling `on_chunk` with the total so far
+#[cfg(feature = "std")]
+fn read_chunks<R: std::io::Read>(
+    r: &mut R,
+    mut on_chunk: impl FnMut(usize) -> error::Result<()>,
+) -> error::Result<Vec<u8>> {
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    let mut src = Vec::new();
+    let mut buf = vec![0; CHUNK_SIZE];
+    loop {
+        let n = match r.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(SacError::io(err)),
+        };
+
+        src.extend_from_slice(&buf[..n]);
+        on_chunk(src.len())?;
+    }
+
+    Ok(src)
+}
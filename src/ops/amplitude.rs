@@ -0,0 +1,130 @@
+use alloc::format;
+
+use crate::binary::SAC_FLOAT_UNDEF;
+use crate::error::{Result, SacError};
+use crate::sac::Sac;
+use crate::SacFileType;
+
+impl Sac {
+    pub(crate) fn check_not_spectral(&self) -> Result<()> {
+        if let SacFileType::RealImag | SacFileType::AmpPhase = self.iftype {
+            let msg = format!("Unsupported for spectral data (iftype = {})", self.iftype);
+            return Err(SacError::custom(msg));
+        }
+
+        Ok(())
+    }
+
+    /// Scales `first` so that its RMS equals `target_rms`. A defined `scale` is
+    /// divided by the same factor, so `first * scale` keeps its physical units.
+    pub fn normalize_rms(&mut self, target_rms: f32) -> Result<()> {
+        self.check_not_spectral()?;
+
+        if !target_rms.is_finite() || target_rms < 0.0 {
+            let msg = format!("Invalid target RMS ({})", target_rms);
+            return Err(SacError::custom(msg));
+        }
+
+        if self.first.is_empty() {
+            return Ok(());
+        }
+
+        let sum: f64 = self.first.iter().map(|v| *v as f64 * *v as f64).sum();
+        let rms = libm::sqrt(sum / self.first.len() as f64);
+        if rms == 0.0 {
+            return Ok(());
+        }
+
+        let factor = (target_rms as f64 / rms) as f32;
+        for v in &mut self.first {
+            *v *= factor;
+        }
+
+        if self.scale != SAC_FLOAT_UNDEF {
+            self.scale /= factor;
+        }
+
+        self.update_extrema();
+        Ok(())
+    }
+
+    /// Multiplies the dependent data by `scale` and resets `scale` to 1. Both
+    /// components are scaled for real/imaginary data. An undefined `scale` is
+    /// left alone.
+    pub fn apply_scale(&mut self) -> Result<()> {
+        if self.scale == SAC_FLOAT_UNDEF || self.scale == 1.0 {
+            return Ok(());
+        }
+
+        if !self.scale.is_finite() {
+            let msg = format!("Invalid scale factor (scale = {})", self.scale);
+            return Err(SacError::custom(msg));
+        }
+
+        let scale = self.scale;
+        self.first.iter_mut().for_each(|v| *v *= scale);
+        if self.iftype == SacFileType::RealImag {
+            self.second.iter_mut().for_each(|v| *v *= scale);
+        }
+
+        self.scale = 1.0;
+        self.update_extrema();
+        Ok(())
+    }
+
+    /// Replaces every sample of `first` with `f(sample)` and refreshes the
+    /// extrema.
+    pub fn map_samples(&mut self, mut f: impl FnMut(f32) -> f32) {
+        self.first.iter_mut().for_each(|v| *v = f(*v));
+        self.update_extrema();
+    }
+
+    /// Like [`Sac::map_samples`], with `f` also given the sample index and its
+    /// time `b + index * delta`.
+    pub fn map_samples_indexed(&mut self, mut f: impl FnMut(usize, f32, f32) -> f32) {
+        let (b, delta) = (self.b as f64, self.delta as f64);
+        for (i, v) in self.first.iter_mut().enumerate() {
+            *v = f(i, (b + i as f64 * delta) as f32, *v);
+        }
+        self.update_extrema();
+    }
+
+    pub fn clip(&mut self, min: f32, max: f32) -> Result<()> {
+        self.check_not_spectral()?;
+
+        if min.is_nan() || max.is_nan() || min > max {
+            let msg = format!("Invalid clip range [{}, {}]", min, max);
+            return Err(SacError::custom(msg));
+        }
+
+        self.first.iter_mut().for_each(|v| *v = v.clamp(min, max));
+        self.update_extrema();
+        Ok(())
+    }
+
+    /// Clips `first` to `n` standard deviations around its mean.
+    pub fn clip_sigma(&mut self, n: f32) -> Result<()> {
+        self.check_not_spectral()?;
+
+        if n.is_nan() || n < 0.0 {
+            let msg = format!("Invalid number of standard deviations ({})", n);
+            return Err(SacError::custom(msg));
+        }
+
+        if self.first.is_empty() {
+            return Ok(());
+        }
+
+        let len = self.first.len() as f64;
+        let mean = self.first.iter().map(|v| *v as f64).sum::<f64>() / len;
+        let var = self
+            .first
+            .iter()
+            .map(|v| (*v as f64 - mean) * (*v as f64 - mean))
+            .sum::<f64>()
+            / len;
+        let width = n as f64 * libm::sqrt(var);
+
+        self.clip((mean - width) as f32, (mean + width) as f32)
+    }
+}
@@ -0,0 +1,149 @@
+use alloc::format;
+use alloc::vec::Vec;
+
+use crate::error::{Result, SacError};
+use crate::sac::Sac;
+
+/// What happened where two merged segments meet.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Junction {
+    /// Distance from the end of the previous segment to the start of the next
+    /// one; negative for an overlap.
+    pub gap_samples: f64,
+    pub gap_seconds: f64,
+    /// Number of fill samples inserted to bridge a gap.
+    pub filled: usize,
+    /// Number of overlapping samples dropped from the start of the next segment.
+    pub trimmed: usize,
+}
+
+/// The junctions of a merge, in time order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MergeReport {
+    pub junctions: Vec<Junction>,
+}
+
+impl Sac {
+    /// Appends the samples of `other`, which must be the same station and
+    /// component with the same sampling and start within half a sample of
+    /// the end of `self`.
+    pub fn append(&mut self, other: &Sac) -> Result<()> {
+        self.check_even_time()?;
+        other.check_even_time()?;
+
+        if self.delta != other.delta {
+            let msg = format!(
+                "Sampling intervals differ (delta = {}, {})",
+                self.delta, other.delta
+            );
+            return Err(SacError::custom(msg));
+        }
+
+        if self.kstnm != other.kstnm || self.kcmpnm != other.kcmpnm {
+            let msg = format!(
+                "Channels differ ({}.{}, {}.{})",
+                self.kstnm, self.kcmpnm, other.kstnm, other.kcmpnm
+            );
+            return Err(SacError::custom(msg));
+        }
+
+        let begins = Sac::absolute_begins(&[&*self, other])?;
+        let delta = self.delta as f64;
+        let offset = (begins[1] - begins[0]) / delta - self.first.len() as f64;
+        if offset.abs() > 0.5 {
+            let kind = if offset > 0.0 { "Gap" } else { "Overlap" };
+            let msg = format!("{} of {:.2} samples between segments", kind, offset.abs());
+            return Err(SacError::custom(msg));
+        }
+
+        self.first.extend_from_slice(&other.first);
+        self.npts = self.first.len() as i32;
+        self.e = self.b + (self.npts - 1).max(0) as f32 * self.delta;
+        self.update_extrema();
+        Ok(())
+    }
+
+    pub fn merge_all(segments: Vec<Sac>) -> Result<Sac> {
+        Sac::merge_all_within(segments, 0.5)
+    }
+
+    /// Like [`Sac::merge_all`], but segments whose start is within `tolerance`
+    /// samples of the expected position are treated as contiguous.
+    pub fn merge_all_within(segments: Vec<Sac>, tolerance: f32) -> Result<Sac> {
+        Sac::merge(segments, tolerance, None).map(|(sac, _)| sac)
+    }
+
+    /// Merges segments like [`Sac::merge_all`], but bridges gaps with `fill`
+    /// samples and drops the overlapping samples of the later segment instead
+    /// of failing. The report records every junction.
+    pub fn merge_fill(segments: Vec<Sac>, fill: f32) -> Result<(Sac, MergeReport)> {
+        Sac::merge(segments, 0.5, Some(fill))
+    }
+
+    fn merge(segments: Vec<Sac>, tolerance: f32, fill: Option<f32>) -> Result<(Sac, MergeReport)> {
+        if segments.is_empty() {
+            return Err(SacError::custom("No segments to merge"));
+        }
+
+        for sac in &segments {
+            sac.check_even_time()?;
+            if sac.delta != segments[0].delta {
+                let msg = format!(
+                    "Sampling intervals differ (delta = {}, {})",
+                    segments[0].delta, sac.delta
+                );
+                return Err(SacError::custom(msg));
+            }
+        }
+
+        let begins = Sac::absolute_begins(&segments)?;
+        let mut segments: Vec<(f64, Sac)> = begins.into_iter().zip(segments).collect();
+        segments.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut iter = segments.into_iter();
+        let (mut end, mut merged) = iter.next().unwrap();
+        let delta = merged.delta as f64;
+        end += merged.first.len() as f64 * delta;
+
+        let mut report = MergeReport::default();
+        for (begin, sac) in iter {
+            let offset = (begin - end) / delta;
+            let mut junction = Junction {
+                gap_samples: offset,
+                gap_seconds: begin - end,
+                filled: 0,
+                trimmed: 0,
+            };
+
+            if offset.abs() > tolerance as f64 {
+                match fill {
+                    Some(value) if offset > 0.0 => {
+                        junction.filled = libm::round(offset) as usize;
+                        let len = merged.first.len() + junction.filled;
+                        merged.first.resize(len, value);
+                    }
+                    Some(_) => {
+                        junction.trimmed = (libm::round(-offset) as usize).min(sac.first.len());
+                    }
+                    None => {
+                        let kind = if offset > 0.0 { "Gap" } else { "Overlap" };
+                        let msg =
+                            format!("{} of {:.2} samples between segments", kind, offset.abs());
+                        return Err(SacError::custom(msg));
+                    }
+                }
+            }
+
+            let kept = &sac.first[junction.trimmed..];
+            end += (junction.filled + kept.len()) as f64 * delta;
+            merged.first.extend_from_slice(kept);
+            report.junctions.push(junction);
+        }
+
+        merged.npts = merged.first.len() as i32;
+        merged.e = merged.b + (merged.npts - 1).max(0) as f32 * merged.delta;
+        merged.update_extrema();
+
+        Ok((merged, report))
+    }
+}
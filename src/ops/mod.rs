@@ -0,0 +1,45 @@
+// Spectral data (iftype IRLIM/IAMPH) keeps its two components in `first` and
+// `second`, and every operation has to keep them consistent:
+//
+// - gains (`apply_scale`) scale both components of real/imaginary data and
+//   only the amplitude of amplitude/phase data
+// - time-domain operations (`normalize_rms`, `clip`, `taper`,
+//   `detrend_window`, `trim_to_common`, `merge_all`, ...) reject spectral data
+//   with an error
+// - `to_amp_phase` and `to_real_imag` convert between the two spectral forms
+//   and reject every other file type
+// - read-only helpers (`update_extrema`, `update_mean`, `has_nonfinite`) work
+//   on any file type; the header extrema describe `first`
+//
+// Failures follow one convention across the data operations:
+//
+// - nothing to do (empty trace, zero RMS, undefined or unit `scale`, an empty
+//   list of traces to `trim_to_common`) succeeds silently and leaves the data
+//   untouched
+// - violated preconditions (wrong file type, uneven spacing, an invalid range,
+//   window, factor or target value) return `Err` and leave the data
+//   untouched; so does merging an empty list, which has no trace to return
+// - indices the caller passes in are checked too: an out-of-range pick index
+//   gives `None` from `pick` and `Err` from `set_pick`; panics are left to
+//   broken invariants inside the crate
+//
+// NaN and infinite samples are not a violated precondition: operations accept
+// them for speed. `check_finite` is the shared opt-in guard that callers run
+// first (`taper_checked` does so for `taper`).
+//
+// Sums (means, RMS, least-squares fits) are accumulated in f64 even though the
+// samples are f32, which keeps them accurate on long traces.
+
+mod amplitude;
+mod merge;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod spectral;
+mod stats;
+mod taper;
+mod time;
+mod trend;
+
+pub use merge::{Junction, MergeReport};
+pub use stats::TraceStats;
+pub use taper::{TaperPlan, TaperWindow};
@@ -0,0 +1,15 @@
+use alloc::vec::Vec;
+
+use rayon::prelude::*;
+
+use crate::error::Result;
+use crate::sac::Sac;
+
+impl Sac {
+    pub fn process_parallel<F>(traces: &mut [Sac], op: F) -> Vec<Result<()>>
+    where
+        F: Fn(&mut Sac) -> Result<()> + Sync,
+    {
+        traces.par_iter_mut().map(&op).collect()
+    }
+}
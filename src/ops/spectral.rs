@@ -0,0 +1,60 @@
+use alloc::format;
+
+use crate::error::{Result, SacError};
+use crate::sac::Sac;
+use crate::SacFileType;
+
+impl Sac {
+    fn check_spectral(&self) -> Result<()> {
+        if !matches!(self.iftype, SacFileType::RealImag | SacFileType::AmpPhase) {
+            let msg = format!("Expected spectral data (iftype = {})", self.iftype);
+            return Err(SacError::custom(msg));
+        }
+
+        if self.first.len() != self.second.len() {
+            let msg = format!(
+                "Spectral components differ in length ({}, {})",
+                self.first.len(),
+                self.second.len()
+            );
+            return Err(SacError::custom(msg));
+        }
+
+        Ok(())
+    }
+
+    /// Converts real/imaginary data to amplitude/phase. A zero amplitude gets
+    /// a phase of 0. Amplitude/phase data is left as it is.
+    pub fn to_amp_phase(&mut self) -> Result<()> {
+        self.check_spectral()?;
+        if self.iftype == SacFileType::AmpPhase {
+            return Ok(());
+        }
+
+        for (re, im) in self.first.iter_mut().zip(self.second.iter_mut()) {
+            let (amp, phase) = (libm::hypotf(*re, *im), libm::atan2f(*im, *re));
+            (*re, *im) = (amp, if amp == 0.0 { 0.0 } else { phase });
+        }
+
+        self.iftype = SacFileType::AmpPhase;
+        self.update_extrema();
+        Ok(())
+    }
+
+    /// Converts amplitude/phase data to real/imaginary. Real/imaginary data is
+    /// left as it is.
+    pub fn to_real_imag(&mut self) -> Result<()> {
+        self.check_spectral()?;
+        if self.iftype == SacFileType::RealImag {
+            return Ok(());
+        }
+
+        for (amp, phase) in self.first.iter_mut().zip(self.second.iter_mut()) {
+            (*amp, *phase) = (*amp * libm::cosf(*phase), *amp * libm::sinf(*phase));
+        }
+
+        self.iftype = SacFileType::RealImag;
+        self.update_extrema();
+        Ok(())
+    }
+}
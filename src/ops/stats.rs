@@ -0,0 +1,125 @@
+use alloc::format;
+
+use crate::error::{Result, SacError};
+use crate::sac::Sac;
+
+/// Summary statistics of the finite samples of a trace.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TraceStats {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub rms: f32,
+}
+
+impl TraceStats {
+    fn of(samples: &[f32]) -> Option<TraceStats> {
+        let mut min = f32::INFINITY;
+        let mut max = f32::NEG_INFINITY;
+        let (mut sum, mut sum_sq) = (0.0, 0.0);
+        let mut count = 0;
+        for v in samples.iter().filter(|v| v.is_finite()) {
+            min = min.min(*v);
+            max = max.max(*v);
+            sum += *v as f64;
+            sum_sq += *v as f64 * *v as f64;
+            count += 1;
+        }
+
+        if count == 0 {
+            return None;
+        }
+
+        Some(TraceStats {
+            min,
+            max,
+            mean: (sum / count as f64) as f32,
+            rms: libm::sqrt(sum_sq / count as f64) as f32,
+        })
+    }
+}
+
+impl Sac {
+    /// Statistics of `first`, skipping non-finite samples. Returns `None` if
+    /// no finite sample is left.
+    pub fn stats(&self) -> Option<TraceStats> {
+        TraceStats::of(&self.first)
+    }
+
+    /// Statistics of the samples in `[start, end]` (times relative to the
+    /// reference, like `b`). The trace is not modified.
+    pub fn stats_window(&self, start: f32, end: f32) -> Result<TraceStats> {
+        self.check_even_time()?;
+
+        let last = self.b + (self.first.len() as f32 - 1.0) * self.delta;
+        if !(start <= end && start >= self.b && end <= last) {
+            let msg = format!(
+                "Window [{}, {}] is outside the data [{}, {}]",
+                start, end, self.b, last
+            );
+            return Err(SacError::custom(msg));
+        }
+
+        let i0 = libm::roundf((start - self.b) / self.delta) as usize;
+        let i1 = libm::roundf((end - self.b) / self.delta) as usize;
+        match TraceStats::of(&self.first[i0..=i1]) {
+            Some(stats) => Ok(stats),
+            None => Err(SacError::custom("Window contains no finite samples")),
+        }
+    }
+
+    /// Recomputes `depmin`, `depmax` and `depmen` from `first`. Non-finite
+    /// samples (e.g. NaN-marked gaps) are skipped; if no finite sample is
+    /// left the header is not changed.
+    pub fn update_extrema(&mut self) {
+        let mut min = f32::INFINITY;
+        let mut max = f32::NEG_INFINITY;
+        let mut sum = 0.0;
+        let mut count = 0;
+        for v in self.first.iter().filter(|v| v.is_finite()) {
+            min = min.min(*v);
+            max = max.max(*v);
+            sum += *v as f64;
+            count += 1;
+        }
+
+        if count == 0 {
+            return;
+        }
+
+        self.depmin = min;
+        self.depmax = max;
+        self.depmen = (sum / count as f64) as f32;
+    }
+
+    /// Recomputes `depmen` from `first`, skipping non-finite samples like
+    /// [`Sac::update_extrema`].
+    pub fn update_mean(&mut self) {
+        let finite = self.first.iter().filter(|v| v.is_finite());
+        let (sum, count) = finite.fold((0.0, 0), |(s, c), v| (s + *v as f64, c + 1));
+
+        if count == 0 {
+            return;
+        }
+
+        self.depmen = (sum / count as f64) as f32;
+    }
+
+    /// Fails if `first` or `second` holds NaN or infinite samples. The data
+    /// operations accept such samples and let them spread, so this is the
+    /// opt-in guard to run before any of them where that must not happen.
+    pub fn check_finite(&self) -> Result<()> {
+        if self.has_nonfinite() {
+            return Err(SacError::custom("Data contains non-finite samples"));
+        }
+
+        Ok(())
+    }
+
+    pub fn has_nonfinite(&self) -> bool {
+        self.first
+            .iter()
+            .chain(&self.second)
+            .any(|v| !v.is_finite())
+    }
+}
@@ -0,0 +1,137 @@
+use alloc::format;
+use alloc::vec::Vec;
+
+use crate::error::{Result, SacError};
+use crate::sac::Sac;
+
+/// How the Hann window of a taper is sampled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum TaperWindow {
+    /// Both ends mirror each other and reach zero at the end sample, as
+    /// filters expect.
+    #[default]
+    Symmetric,
+    /// One period of the window as used for FFTs: the falling end is the
+    /// symmetric window one sample longer with its last point dropped, so only
+    /// the first sample reaches zero.
+    Periodic,
+}
+
+/// Taper coefficients computed once for traces of one length, for tapering
+/// many traces with [`Sac::taper_with`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaperPlan {
+    len: usize,
+    rise: Vec<f32>,
+    fall: Vec<f32>,
+}
+
+impl TaperPlan {
+    /// A half cosine (Hann) taper over `n` samples at each end of a trace of
+    /// `len` samples. `n` is at most half of `len`.
+    pub fn new(len: usize, n: usize) -> Result<TaperPlan> {
+        TaperPlan::with_window(len, n, TaperWindow::Symmetric)
+    }
+
+    /// Like [`TaperPlan::new`], with the window sampled as `window`. A
+    /// single-sample taper (`n == 1`) multiplies the first sample by zero
+    /// and the last by zero (symmetric) or one (periodic); `n == 0` leaves
+    /// the trace as is.
+    pub fn with_window(len: usize, n: usize, window: TaperWindow) -> Result<TaperPlan> {
+        if n > len / 2 {
+            let msg = format!("Invalid taper length ({} of {} samples)", n, len);
+            return Err(SacError::custom(msg));
+        }
+
+        let hann = |i: usize| {
+            (0.5 * (1.0 - libm::cos(core::f64::consts::PI * i as f64 / n as f64))) as f32
+        };
+        let rise: Vec<f32> = (0..n).map(hann).collect();
+        let fall = match window {
+            TaperWindow::Symmetric => rise.clone(),
+            TaperWindow::Periodic => (1..=n).map(hann).collect(),
+        };
+        Ok(TaperPlan { len, rise, fall })
+    }
+
+    /// Like [`TaperPlan::new`], tapering `round(width * len)` samples at each
+    /// end for a `width` from 0 to 0.5.
+    pub fn from_fraction(len: usize, width: f64) -> Result<TaperPlan> {
+        if !(0.0..=0.5).contains(&width) {
+            let msg = format!("Invalid taper width ({})", width);
+            return Err(SacError::custom(msg));
+        }
+
+        let n = (libm::round(width * len as f64) as usize).min(len / 2);
+        TaperPlan::new(len, n)
+    }
+
+    /// Number of samples of the traces the plan applies to.
+    pub fn trace_len(&self) -> usize {
+        self.len
+    }
+
+    /// Number of tapered samples at each end.
+    pub fn taper_len(&self) -> usize {
+        self.rise.len()
+    }
+}
+
+impl Sac {
+    /// Alias for [`Sac::taper_fraction`], the unit SAC's own `taper` command
+    /// uses.
+    pub fn taper(&mut self, width: f32) -> Result<()> {
+        self.taper_fraction(width as f64)
+    }
+
+    /// Tapers `width` (0 to 0.5) of the samples at each end of `first` with a
+    /// symmetric half cosine (Hann) window, so the end samples become zero. The taper
+    /// covers `round(width * len)` samples, at most half of the trace.
+    pub fn taper_fraction(&mut self, width: f64) -> Result<()> {
+        self.check_not_spectral()?;
+        let plan = TaperPlan::from_fraction(self.first.len(), width)?;
+        self.taper_with(&plan)
+    }
+
+    /// Like [`Sac::taper_fraction`], with the taper length given as `n`
+    /// samples at each end, at most half of the trace.
+    pub fn taper_samples(&mut self, n: usize) -> Result<()> {
+        self.check_not_spectral()?;
+        let plan = TaperPlan::new(self.first.len(), n)?;
+        self.taper_with(&plan)
+    }
+
+    /// Tapers `first` with the precomputed coefficients of `plan`, which must
+    /// have been made for the length of the trace.
+    pub fn taper_with(&mut self, plan: &TaperPlan) -> Result<()> {
+        self.check_not_spectral()?;
+
+        let len = self.first.len();
+        if plan.len != len {
+            let msg = format!("Taper plan is for {} samples, found {}", plan.len, len);
+            return Err(SacError::custom(msg));
+        }
+
+        for (i, (rise, fall)) in plan.rise.iter().zip(&plan.fall).enumerate() {
+            self.first[i] *= rise;
+            self.first[len - 1 - i] *= fall;
+        }
+
+        self.update_extrema();
+        Ok(())
+    }
+
+    /// A copy tapered as by [`Sac::taper`]; `self` is left as is.
+    pub fn tapered(&self, width: f32) -> Result<Sac> {
+        let mut s = self.clone();
+        s.taper(width)?;
+        Ok(s)
+    }
+
+    /// Like [`Sac::taper`], but fails instead of tapering if the data holds
+    /// NaN or infinite samples.
+    pub fn taper_checked(&mut self, width: f32) -> Result<()> {
+        self.check_finite()?;
+        self.taper(width)
+    }
+}
@@ -0,0 +1,206 @@
+use alloc::format;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use crate::binary::SAC_FLOAT_UNDEF;
+use crate::error::{Result, SacError};
+use crate::sac::Sac;
+use crate::SacFileType;
+
+impl Sac {
+    pub(crate) fn check_even_time(&self) -> Result<()> {
+        if self.iftype != SacFileType::Time || !self.leven {
+            let msg = format!(
+                "Expected evenly sampled time series (iftype = {}, leven = {})",
+                self.iftype, self.leven
+            );
+            return Err(SacError::custom(msg));
+        }
+
+        if self.delta <= 0.0 || !self.delta.is_finite() {
+            let msg = format!("Invalid sampling interval (delta = {})", self.delta);
+            return Err(SacError::custom(msg));
+        }
+
+        Ok(())
+    }
+
+    // `npts` and `e` matching the length of `first`
+    pub(crate) fn synced_lengths(&self) -> Result<(i32, f32)> {
+        // A header read without its data keeps the lengths it describes
+        if self.first.is_empty() && self.second.is_empty() {
+            return Ok((self.npts, self.e));
+        }
+
+        let npts = match i32::try_from(self.first.len()) {
+            Ok(v) => v,
+            Err(_) => {
+                let msg = format!("Too many samples for npts ({})", self.first.len());
+                return Err(SacError::custom(msg));
+            }
+        };
+
+        let e = if self.iftype == SacFileType::Time && self.leven {
+            self.b + (npts - 1).max(0) as f32 * self.delta
+        } else {
+            self.e
+        };
+
+        Ok((npts, e))
+    }
+
+    /// Sets `npts` to the length of `first` and, for evenly sampled time
+    /// series, `e` to the time of the last sample. A header without any data
+    /// is left as is. [`Sac::to_slice`] writes the same values;
+    /// [`Sac::to_slice_raw`] writes the header as is.
+    pub fn sync_lengths(&mut self) -> Result<()> {
+        (self.npts, self.e) = self.synced_lengths()?;
+        Ok(())
+    }
+
+    /// Cuts the trace to `[t - before, t + after]`, where `t` is the time of
+    /// `marker` (`b`, `e`, `o`, `a` or `t0`..`t9`). Undefined markers and
+    /// windows reaching outside the data are errors.
+    pub fn cut_around(&mut self, marker: &str, before: f32, after: f32) -> Result<()> {
+        self.check_even_time()?;
+
+        let time = match marker.to_ascii_lowercase().as_str() {
+            "b" => self.b,
+            "e" => self.e,
+            "o" => self.o,
+            "a" => self.a,
+            m => match m.strip_prefix('t').and_then(|i| i.parse::<usize>().ok()) {
+                Some(i) if i < 10 => self.t[i],
+                _ => return Err(SacError::custom(format!("Unknown marker ({})", marker))),
+            },
+        };
+
+        if time == SAC_FLOAT_UNDEF {
+            return Err(SacError::custom(format!("Undefined marker ({})", marker)));
+        }
+
+        let (start, end) = (time - before, time + after);
+        let last = self.first.len() as f32 - 1.0;
+        let i0 = libm::roundf((start - self.b) / self.delta);
+        let i1 = libm::roundf((end - self.b) / self.delta);
+        if !(0.0 <= i0 && i0 <= i1 && i1 <= last) {
+            let msg = format!(
+                "Window [{}, {}] is outside the data [{}, {}]",
+                start,
+                end,
+                self.b,
+                self.b + last * self.delta
+            );
+            return Err(SacError::custom(msg));
+        }
+
+        self.cut_index(i0 as usize, i1 as usize + 1);
+        Ok(())
+    }
+
+    /// Keeps every `factor`-th sample and multiplies `delta` by `factor`.
+    /// No anti-alias filter is applied, so energy above the new Nyquist
+    /// frequency folds into the result; low-pass the trace first if it has any.
+    pub fn decimate(&mut self, factor: usize) -> Result<()> {
+        self.check_even_time()?;
+
+        if factor == 0 {
+            return Err(SacError::custom("Invalid decimation factor (0)"));
+        }
+
+        self.first = self.first.iter().step_by(factor).cloned().collect();
+        self.delta *= factor as f32;
+        self.npts = self.first.len() as i32;
+        self.e = self.b + (self.npts - 1).max(0) as f32 * self.delta;
+        self.update_extrema();
+        Ok(())
+    }
+
+    // Keeps the samples in `start..end` and moves `b` to the first kept sample
+    pub(crate) fn cut_index(&mut self, start: usize, end: usize) {
+        self.first.truncate(end);
+        self.first.drain(..start.min(self.first.len()));
+
+        self.b += start as f32 * self.delta;
+        self.npts = self.first.len() as i32;
+        self.e = self.b + (self.npts - 1).max(0) as f32 * self.delta;
+        self.update_extrema();
+    }
+
+    // Absolute begin time of every trace, relative to 1970-01-01 when all
+    // reference times are defined and to the shared reference otherwise
+    pub(crate) fn absolute_begins<T: Borrow<Sac>>(traces: &[T]) -> Result<Vec<f64>> {
+        let refs: Vec<Option<f64>> = traces
+            .iter()
+            .map(|s| s.borrow().reference_seconds())
+            .collect();
+        if refs.iter().any(|r| r.is_some()) && refs.iter().any(|r| r.is_none()) {
+            return Err(SacError::custom(
+                "Reference time is undefined for some traces",
+            ));
+        }
+
+        let begins = traces
+            .iter()
+            .zip(refs)
+            .map(|(s, r)| r.unwrap_or(0.0) + s.borrow().b as f64)
+            .collect();
+
+        Ok(begins)
+    }
+
+    pub fn trim_to_common(traces: &mut [Sac]) -> Result<()> {
+        if traces.is_empty() {
+            return Ok(());
+        }
+
+        for sac in traces.iter() {
+            sac.check_even_time()?;
+        }
+
+        let begins = Sac::absolute_begins(traces)?;
+
+        let mut start = f64::NEG_INFINITY;
+        let mut end = f64::INFINITY;
+        for (sac, b) in traces.iter().zip(&begins) {
+            let length = sac.first.len() as f64 - 1.0;
+            start = start.max(*b);
+            end = end.min(b + length * sac.delta as f64);
+        }
+
+        if start > end {
+            return Err(SacError::custom("No common time window"));
+        }
+
+        let delta = traces[0].delta;
+        if let Some(sac) = traces.iter().find(|s| s.delta != delta) {
+            let msg = format!(
+                "Sampling intervals differ (delta = {}, {})",
+                delta, sac.delta
+            );
+            return Err(SacError::custom(msg));
+        }
+
+        let bounds: Vec<(usize, usize)> = traces
+            .iter()
+            .zip(&begins)
+            .map(|(sac, b)| {
+                let delta = sac.delta as f64;
+                let i0 = libm::round((start - b) / delta) as usize;
+                let i1 = libm::round((end - b) / delta) as usize + 1;
+                (i0, i1)
+            })
+            .collect();
+
+        let len = bounds[0].1 - bounds[0].0;
+        if bounds.iter().any(|(i0, i1)| i1 - i0 != len) {
+            return Err(SacError::custom("Traces differ in length after trimming"));
+        }
+
+        for (sac, (i0, i1)) in traces.iter_mut().zip(bounds) {
+            sac.cut_index(i0, i1);
+        }
+
+        Ok(())
+    }
+}
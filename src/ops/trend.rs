@@ -0,0 +1,93 @@
+use alloc::format;
+
+use crate::error::{Result, SacError};
+use crate::sac::Sac;
+use crate::waveform::Waveform;
+use crate::SacFileType;
+
+impl Sac {
+    fn check_time(&self) -> Result<()> {
+        if self.iftype != SacFileType::Time {
+            let msg = format!("Expected time series data (iftype = {})", self.iftype);
+            return Err(SacError::custom(msg));
+        }
+
+        Ok(())
+    }
+
+    /// Subtracts the mean of `first` ([`Waveform::demean`]) and refreshes the
+    /// extrema. Spectral and XY data are rejected.
+    pub fn rmean(&mut self) -> Result<()> {
+        self.check_time()?;
+        self.demean();
+        Ok(())
+    }
+
+    /// Subtracts the least-squares line over the sample index
+    /// ([`Waveform::detrend`]) and refreshes the extrema. Spectral and XY data
+    /// are rejected.
+    pub fn rtrend(&mut self) -> Result<()> {
+        self.check_time()?;
+        self.detrend();
+        Ok(())
+    }
+
+    /// A copy with the mean removed as by [`Waveform::demean`]; `self` is left
+    /// as is.
+    pub fn demeaned(&self) -> Sac {
+        let mut s = self.clone();
+        s.demean();
+        s
+    }
+
+    /// A copy with the trend removed as by [`Waveform::detrend`]; `self` is
+    /// left as is.
+    pub fn detrended(&self) -> Sac {
+        let mut s = self.clone();
+        s.detrend();
+        s
+    }
+
+    /// Fits a least-squares line over the samples in `[start, end]` (times
+    /// relative to the reference, like `b`) and subtracts it from the whole
+    /// trace.
+    pub fn detrend_window(&mut self, start: f32, end: f32) -> Result<()> {
+        self.check_even_time()?;
+
+        let last = self.b + (self.first.len() as f32 - 1.0) * self.delta;
+        if !(start < end && start >= self.b && end <= last) {
+            let msg = format!(
+                "Window [{}, {}] is outside the data [{}, {}]",
+                start, end, self.b, last
+            );
+            return Err(SacError::custom(msg));
+        }
+
+        let i0 = libm::roundf((start - self.b) / self.delta) as usize;
+        let i1 = libm::roundf((end - self.b) / self.delta) as usize;
+        if i1 <= i0 {
+            return Err(SacError::custom("Window contains fewer than two samples"));
+        }
+
+        let (mut sx, mut sy, mut sxx, mut sxy) = (0.0, 0.0, 0.0, 0.0);
+        for (i, y) in self.first[i0..=i1].iter().enumerate() {
+            let x = (i0 + i) as f64;
+            let y = *y as f64;
+            sx += x;
+            sy += y;
+            sxx += x * x;
+            sxy += x * y;
+        }
+
+        let n = (i1 - i0 + 1) as f64;
+        let slope = (n * sxy - sx * sy) / (n * sxx - sx * sx);
+        let intercept = (sy - slope * sx) / n;
+
+        for (i, y) in self.first.iter_mut().enumerate() {
+            *y -= (intercept + slope * i as f64) as f32;
+        }
+
+        self.update_extrema();
+        Ok(())
+    }
+}
@@ -1,13 +1,22 @@
+use alloc::format;
+use alloc::string::ToString;
 use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
 use core::ops::{Deref, DerefMut};
 
 use crate::binary::SacBinary;
-use crate::header::SacHeader;
+use crate::date::RefTime;
+use crate::enums::{SacFileType, SacZeroType, SpectralKind};
+use crate::error::{Result, SacError};
+use crate::footer::{SacFooter, SAC_FOOTER_VERSION};
+use crate::header::{bits_eq, SacHeader};
 
+#[derive(Debug, Clone)]
 pub struct Sac {
     pub(crate) h: SacHeader,
     pub first: Vec<f32>,
     pub second: Vec<f32>,
+    pub(crate) footer: Option<SacFooter>,
 }
 
 impl Deref for Sac {
@@ -24,16 +33,165 @@ impl DerefMut for Sac {
     }
 }
 
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Components<'a> {
+    Single(&'a [f32]),
+    Pair {
+        a: &'a [f32],
+        b: &'a [f32],
+        kind: SpectralKind,
+    },
+    Xy {
+        x: &'a [f32],
+        y: &'a [f32],
+    },
+}
+
+impl Hash for Sac {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.h.hash(state);
+        for data in [&self.first, &self.second] {
+            state.write_usize(data.len());
+            data.iter().for_each(|v| state.write_u32(v.to_bits()));
+        }
+        self.footer.map(|f| f.bits()).hash(state);
+    }
+}
+
+impl PartialEq for Sac {
+    fn eq(&self, other: &Self) -> bool {
+        self.h == other.h
+            && bits_eq(&self.first, &other.first)
+            && bits_eq(&self.second, &other.second)
+            && match (&self.footer, &other.footer) {
+                (Some(a), Some(b)) => a.bits_eq(b),
+                (a, b) => a.is_none() && b.is_none(),
+            }
+    }
+}
+
+impl Eq for Sac {}
+
 impl Sac {
     pub(crate) fn build(b: &SacBinary) -> Self {
         Sac {
             h: SacHeader::from(b),
             first: Vec::with_capacity(0),
             second: Vec::with_capacity(0),
+            footer: None,
         }
     }
 
     pub fn new() -> Self {
         Sac::build(&SacBinary::default())
     }
+
+    /// The double-precision footer that a version 7 file carries (and that
+    /// [`Sac::to_slice`] writes), or `None` for other versions. Values read
+    /// from a file are kept until the matching header field is changed.
+    pub fn footer(&self) -> Option<SacFooter> {
+        if self.nvhdr != SAC_FOOTER_VERSION {
+            return None;
+        }
+
+        Some(SacFooter::reconcile(self.footer.as_ref(), &self.h))
+    }
+
+    /// An evenly sampled time series starting at `b = 0`, with the derived
+    /// fields (`npts`, `e`, extrema) filled in. Fails if `reftime` is not a
+    /// valid reference time.
+    pub fn synthetic(
+        data: Vec<f32>,
+        delta: f32,
+        kstnm: &str,
+        reftime: Option<RefTime>,
+    ) -> Result<Sac> {
+        let mut sac = Sac::new();
+        sac.iftype = SacFileType::Time;
+        sac.leven = true;
+        sac.delta = delta;
+        sac.b = 0.0;
+        sac.kstnm = kstnm.to_string();
+        if let Some(t) = reftime {
+            sac.set_reference_time(t)?;
+            sac.iztype = SacZeroType::Begin;
+        }
+
+        sac.first = data;
+        sac.npts = sac.first.len() as i32;
+        sac.e = (sac.npts - 1).max(0) as f32 * delta;
+        sac.update_extrema();
+        Ok(sac)
+    }
+
+    pub fn from_xy_traces(x: &Sac, y: &Sac) -> Result<Sac> {
+        x.check_not_spectral()?;
+        y.check_not_spectral()?;
+
+        if x.first.len() != y.first.len() {
+            let msg = format!(
+                "Trace lengths differ (x = {}, y = {})",
+                x.first.len(),
+                y.first.len()
+            );
+            return Err(SacError::custom(msg));
+        }
+
+        let mut sac = Sac::new();
+        sac.iftype = SacFileType::XY;
+        sac.leven = false;
+        sac.npts = y.first.len() as i32;
+        sac.delta = y.delta;
+        sac.first = y.first.clone();
+        sac.second = x.first.clone();
+        if let (Some(b), Some(e)) = (x.first.first(), x.first.last()) {
+            sac.b = *b;
+            sac.e = *e;
+        }
+
+        sac.kstnm = y.kstnm.clone();
+        sac.knetwk = y.knetwk.clone();
+        sac.kcmpnm = y.kcmpnm.clone();
+        sac.update_extrema();
+
+        Ok(sac)
+    }
+
+    /// Time of every sample: `b + i * delta` for evenly sampled data, the
+    /// stored x axis (`second`) otherwise. Empty if no data was loaded.
+    pub fn times(&self) -> Vec<f32> {
+        if !self.leven {
+            return self.second.clone();
+        }
+
+        let (b, delta) = (self.b as f64, self.delta as f64);
+        (0..self.first.len())
+            .map(|i| (b + i as f64 * delta) as f32)
+            .collect()
+    }
+
+    pub fn components(&self) -> Components<'_> {
+        let (a, b) = (self.first.as_slice(), self.second.as_slice());
+        match self.iftype {
+            SacFileType::Time if self.leven => Components::Single(a),
+            SacFileType::Time | SacFileType::XY => Components::Xy { x: b, y: a },
+            SacFileType::RealImag => Components::Pair {
+                a,
+                b,
+                kind: SpectralKind::RealImag,
+            },
+            SacFileType::AmpPhase => Components::Pair {
+                a,
+                b,
+                kind: SpectralKind::AmpPhase,
+            },
+            SacFileType::Unknown(_) => Components::Single(a),
+        }
+    }
+}
+
+impl Default for Sac {
+    fn default() -> Self {
+        Self::new()
+    }
 }
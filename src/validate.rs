@@ -0,0 +1,111 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::binary::SAC_FLOAT_UNDEF;
+use crate::sac::Sac;
+use crate::{SacFileType, SAC_HEADER_VERSIONS};
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn error(message: String) -> Self {
+        ValidationIssue {
+            severity: Severity::Error,
+            message,
+        }
+    }
+
+    fn warning(message: String) -> Self {
+        ValidationIssue {
+            severity: Severity::Warning,
+            message,
+        }
+    }
+}
+
+#[inline]
+fn out_of_range(v: f32, min: f32, max: f32) -> bool {
+    v != SAC_FLOAT_UNDEF && !(min..=max).contains(&v)
+}
+
+impl Sac {
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        if !SAC_HEADER_VERSIONS.contains(&self.nvhdr) {
+            let msg = format!("Unsupported major version (nvhdr = {})", self.nvhdr);
+            issues.push(ValidationIssue::error(msg));
+        }
+
+        if let SacFileType::Unknown(v) = self.iftype {
+            let msg = format!("Unsupported file type (iftype = {})", v);
+            issues.push(ValidationIssue::error(msg));
+        }
+
+        let even = self.iftype == SacFileType::Time && self.leven;
+        if even && (self.delta <= 0.0 || !self.delta.is_finite()) {
+            let msg = format!("Invalid sampling interval (delta = {})", self.delta);
+            issues.push(ValidationIssue::error(msg));
+        }
+
+        let loaded = !(self.first.is_empty() && self.second.is_empty());
+        if self.npts < 0 {
+            let msg = format!("Negative number of points (npts = {})", self.npts);
+            issues.push(ValidationIssue::error(msg));
+        } else if loaded {
+            let npts = self.npts as usize;
+            if self.first.len() != npts || (!even && self.second.len() != npts) {
+                let msg = format!(
+                    "Data length does not match npts (npts = {}, first = {}, second = {})",
+                    self.npts,
+                    self.first.len(),
+                    self.second.len()
+                );
+                issues.push(ValidationIssue::error(msg));
+            }
+        }
+
+        if even && self.npts > 0 && self.delta > 0.0 {
+            let e = self.b + (self.npts - 1) as f32 * self.delta;
+            if (e - self.e).abs() > self.delta / 2.0 {
+                let msg = format!("End time is inconsistent (e = {}, expected {})", self.e, e);
+                issues.push(ValidationIssue::warning(msg));
+            }
+        }
+
+        let ranges = [
+            ("stla", self.stla, -90.0, 90.0),
+            ("evla", self.evla, -90.0, 90.0),
+            ("stlo", self.stlo, -180.0, 360.0),
+            ("evlo", self.evlo, -180.0, 360.0),
+            ("cmpaz", self.cmpaz, 0.0, 360.0),
+            ("cmpinc", self.cmpinc, 0.0, 180.0),
+        ];
+        for (name, v, min, max) in ranges {
+            if out_of_range(v, min, max) {
+                let msg = format!(
+                    "Value out of range ({} = {}, expected {}..={})",
+                    name, v, min, max
+                );
+                issues.push(ValidationIssue::warning(msg));
+            }
+        }
+
+        if let Err(err) = self.validate_ascii() {
+            issues.push(ValidationIssue::warning(err.to_string()));
+        }
+
+        issues
+    }
+}
@@ -0,0 +1,107 @@
+use alloc::vec::Vec;
+
+use crate::error::Result;
+use crate::sac::Sac;
+
+pub trait Waveform {
+    fn data(&self) -> &[f32];
+
+    fn data_mut(&mut self) -> &mut Vec<f32>;
+
+    fn delta(&self) -> f32;
+
+    /// Called after the default methods have modified the samples, so
+    /// implementors can refresh derived values.
+    fn data_changed(&mut self) {}
+
+    fn demean(&mut self) {
+        let data = self.data_mut();
+        if data.is_empty() {
+            return;
+        }
+
+        let mean = data.iter().map(|v| *v as f64).sum::<f64>() / data.len() as f64;
+        data.iter_mut().for_each(|v| *v = (*v as f64 - mean) as f32);
+        self.data_changed();
+    }
+
+    /// Removes the least-squares line fitted over the sample index.
+    fn detrend(&mut self) {
+        self.detrend_coeffs();
+    }
+
+    /// Like [`Waveform::detrend`], returning the removed line as `(slope,
+    /// intercept)`: the slope per unit of time (`delta`) and the value at the
+    /// first sample. Nothing is removed from fewer than two samples, which
+    /// gives `(0.0, 0.0)`.
+    fn detrend_coeffs(&mut self) -> (f32, f32) {
+        let delta = self.delta();
+        let data = self.data_mut();
+        if data.len() < 2 {
+            return (0.0, 0.0);
+        }
+
+        let (mut sx, mut sy, mut sxx, mut sxy) = (0.0, 0.0, 0.0, 0.0);
+        for (i, y) in data.iter().enumerate() {
+            let x = i as f64;
+            let y = *y as f64;
+            sx += x;
+            sy += y;
+            sxx += x * x;
+            sxy += x * y;
+        }
+
+        let n = data.len() as f64;
+        let slope = (n * sxy - sx * sy) / (n * sxx - sx * sx);
+        let intercept = (sy - slope * sx) / n;
+
+        for (i, y) in data.iter_mut().enumerate() {
+            *y = (*y as f64 - intercept - slope * i as f64) as f32;
+        }
+        self.data_changed();
+
+        ((slope / delta as f64) as f32, intercept as f32)
+    }
+}
+
+impl Waveform for Sac {
+    fn data(&self) -> &[f32] {
+        &self.first
+    }
+
+    fn data_mut(&mut self) -> &mut Vec<f32> {
+        &mut self.first
+    }
+
+    fn delta(&self) -> f32 {
+        self.h.delta
+    }
+
+    fn data_changed(&mut self) {
+        self.update_extrema();
+    }
+}
+
+/// Applies operations to every trace of a gather.
+pub trait SacSliceExt {
+    fn demean_all(&mut self);
+
+    fn detrend_all(&mut self);
+
+    /// Applies `op` to every trace in order, stopping at the first error.
+    fn try_apply_all<F: FnMut(&mut Sac) -> Result<()>>(&mut self, op: F) -> Result<()>;
+}
+
+impl SacSliceExt for [Sac] {
+    fn demean_all(&mut self) {
+        self.iter_mut().for_each(Waveform::demean);
+    }
+
+    fn detrend_all(&mut self) {
+        self.iter_mut().for_each(Waveform::detrend);
+    }
+
+    fn try_apply_all<F: FnMut(&mut Sac) -> Result<()>>(&mut self, op: F) -> Result<()> {
+        self.iter_mut().try_for_each(op)
+    }
+}
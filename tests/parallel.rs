@@ -0,0 +1,23 @@
+#![cfg(feature = "rayon")]
+
+use std::path::Path;
+
+use sac::{Endian, Sac};
+
+#[test]
+fn process_parallel() {
+    let path = Path::new("tests/test.sac");
+    let mut traces: Vec<Sac> = (0..8)
+        .map(|_| Sac::from_file(path, Endian::Little).unwrap())
+        .collect();
+
+    let results = Sac::process_parallel(&mut traces, |sac| sac.normalize_rms(1.0));
+
+    assert_eq!(results.len(), traces.len());
+    assert!(results.iter().all(|r| r.is_ok()));
+    for sac in &traces {
+        let y = &sac.first;
+        let rms = (y.iter().map(|v| v * v).sum::<f32>() / y.len() as f32).sqrt();
+        assert!((rms - 1.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,78 @@
+use proptest::prelude::*;
+
+use sac::{Endian, Sac, SacFileType};
+
+macro_rules! assign {
+    ($sac:ident, $iter:ident, $($field:ident),+) => {
+        $($sac.$field = $iter.next().unwrap();)+
+    };
+}
+
+fn file_type() -> impl Strategy<Value = SacFileType> {
+    prop_oneof![
+        Just(SacFileType::Time),
+        Just(SacFileType::RealImag),
+        Just(SacFileType::AmpPhase),
+        Just(SacFileType::XY),
+    ]
+}
+
+prop_compose! {
+    fn arb_sac()(
+        floats in prop::collection::vec(any::<f32>(), 30),
+        arrays in prop::collection::vec(any::<[f32; 10]>(), 3),
+        ints in prop::collection::vec(any::<i32>(), 24),
+        bools in any::<[bool; 4]>(),
+        strings in prop::collection::vec("[A-Za-z0-9_.-]{0,8}", 23),
+        kevnm in "[A-Za-z0-9_.-]{0,16}",
+        iftype in file_type(),
+        data in prop::collection::vec(any::<f32>(), 0..64),
+    ) -> Sac {
+        let mut sac = Sac::new();
+
+        let mut f = floats.into_iter();
+        assign!(sac, f, delta, depmin, depmax, scale, odelta, b, e, o, a, f, stla, stlo, stel);
+        assign!(sac, f, stdp, evla, evlo, evel, evdp, mag, dist, az, baz, gcarc, depmen, cmpaz);
+        assign!(sac, f, cmpinc, xminimum, xmaximum, yminimum, ymaximum);
+
+        let mut a = arrays.into_iter();
+        assign!(sac, a, t, resp, user);
+
+        let mut i = ints.into_iter();
+        assign!(sac, i, nzyear, nzjday, nzhour, nzmin, nzsec, nzmsec, norid, nevid, nwfid);
+        assign!(sac, i, nxsize, nysize, iinst, istreg, ievreg, ievtyp, iqual);
+        assign!(sac, i, isynth, imagtyp, imagsrc);
+        sac.idep = i.next().unwrap().into();
+        sac.iztype = i.next().unwrap().into();
+
+        let mut l = bools.into_iter();
+        assign!(sac, l, leven, lpspol, lovrok, lcalda);
+
+        let mut k = strings.into_iter();
+        assign!(sac, k, kstnm, khole, ko, ka, kf, kuser0, kuser1, kuser2, kcmpnm, knetwk);
+        assign!(sac, k, kdatrd, kinst);
+        sac.kt = core::array::from_fn(|_| k.next().unwrap());
+        sac.kevnm = kevnm;
+
+        sac.iftype = iftype;
+        if iftype != SacFileType::Time || !sac.leven {
+            sac.second = data.iter().map(|v| v * 2.0).collect();
+        }
+        sac.first = data;
+        sac.sync_lengths().unwrap();
+        sac.update_extrema();
+
+        sac
+    }
+}
+
+proptest! {
+    #[test]
+    fn roundtrip(sac in arb_sac()) {
+        for endian in [Endian::Little, Endian::Big] {
+            let bytes = sac.to_slice(endian).unwrap();
+            let decoded = Sac::from_slice(&bytes, endian).unwrap();
+            prop_assert!(decoded == sac);
+        }
+    }
+}
@@ -1,7 +1,15 @@
+use std::collections::HashSet;
 use std::fs;
+use std::io::Cursor;
 use std::path::Path;
+use std::time::{Duration, Instant};
 
-use sac::{Endian, Sac, SacFileType};
+use sac::dsp;
+use sac::error::SacError;
+use sac::{
+    Components, Endian, Sac, SacDepType, SacFileType, SacZeroType, Severity, SpectralKind,
+    TaperPlan, TaperWindow,
+};
 
 #[test]
 fn read() {
@@ -14,7 +22,7 @@ fn read() {
     assert_eq!(sac.kstnm, "CDV");
 
     assert_eq!(y.first().unwrap(), &-0.09728001);
-    assert_eq!(y.last().unwrap(), &-0.07680000);
+    assert_eq!(y.last().unwrap(), &-0.0768);
     assert_eq!(y.len(), sac.npts as usize);
 }
 
@@ -34,7 +42,7 @@ fn write() {
     assert_eq!(sac.kstnm, "CDV");
 
     assert_eq!(y.first().unwrap(), &-0.09728001);
-    assert_eq!(y.last().unwrap(), &-0.07680000);
+    assert_eq!(y.last().unwrap(), &-0.0768);
     assert_eq!(y.len(), sac.npts as usize);
 
     fs::remove_file(new).unwrap();
@@ -60,3 +68,1677 @@ fn new() {
 
     fs::remove_file(new).unwrap();
 }
+
+#[test]
+fn nonfinite() {
+    let path = Path::new("tests/test.sac");
+    let mut sac = Sac::from_file(path, Endian::Little).unwrap();
+    assert!(!sac.has_nonfinite());
+
+    sac.first[10] = f32::NAN;
+    assert!(sac.has_nonfinite());
+}
+
+#[test]
+fn check_finite() {
+    let mut sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    sac.check_finite().unwrap();
+
+    sac.first[10] = f32::INFINITY;
+    let before = sac.clone();
+    type Op = fn(&mut Sac) -> Result<(), SacError>;
+    let ops: [Op; 3] = [
+        |s| s.normalize_rms(1.0),
+        |s| s.clip(-1.0, 1.0),
+        |s| s.rmean(),
+    ];
+    for op in ops {
+        let err = sac.check_finite().and_then(|_| op(&mut sac)).unwrap_err();
+        assert!(format!("{}", err).contains("non-finite"));
+        assert_eq!(sac, before);
+    }
+}
+
+#[test]
+fn convert_endian() {
+    let path = Path::new("tests/test.sac");
+    let big = Path::new("tests/test_convert_big.sac");
+    let little = Path::new("tests/test_convert_little.sac");
+
+    Sac::convert_endian_file(path, big, Endian::Little, Endian::Big).unwrap();
+    Sac::convert_endian_file(big, little, Endian::Big, Endian::Little).unwrap();
+
+    let sac = Sac::from_file(big, Endian::Big).unwrap();
+    assert_eq!(sac.npts, 1000);
+    assert_eq!(fs::read(path).unwrap(), fs::read(little).unwrap());
+
+    fs::remove_file(big).unwrap();
+    fs::remove_file(little).unwrap();
+}
+
+#[test]
+fn convert_endian_v7_without_footer() {
+    let mut src = fs::read("tests/test.sac").unwrap();
+    src[304..308].copy_from_slice(&7i32.to_le_bytes());
+    let sac = Sac::from_slice(&src, Endian::Little).unwrap();
+
+    let little = Path::new("tests/test_convert_v7.sac");
+    let big = Path::new("tests/test_convert_v7_big.sac");
+    fs::write(little, &src).unwrap();
+    Sac::convert_endian_file(little, big, Endian::Little, Endian::Big).unwrap();
+
+    let converted = Sac::from_file(big, Endian::Big).unwrap();
+    assert_eq!(converted.first, sac.first);
+    assert_eq!(converted.e, sac.e);
+
+    fs::remove_file(little).unwrap();
+    fs::remove_file(big).unwrap();
+}
+
+#[test]
+fn file_type_display() {
+    assert_eq!(format!("{}", SacFileType::Time), "ITIME");
+    assert_eq!(format!("{}", SacFileType::RealImag), "IRLIM");
+    assert_eq!(format!("{}", SacFileType::AmpPhase), "IAMPH");
+    assert_eq!(format!("{}", SacFileType::XY), "IXY");
+    assert_eq!(format!("{}", SacFileType::Unknown(52)), "IUNKN(52)");
+
+    // Unknown codes print the same way for every enum
+    assert_eq!(format!("{}", SacDepType::Velocity), "IVEL");
+    assert_eq!(format!("{}", SacDepType::Unknown(99)), "IUNKN(99)");
+    assert_eq!(format!("{}", SacZeroType::Pick(3)), "IT3");
+    assert_eq!(format!("{}", SacZeroType::Unknown(99)), "IUNKN(99)");
+}
+
+#[test]
+fn read_header() {
+    let src = fs::read("tests/test.sac").unwrap();
+    let mut cursor = Cursor::new(src);
+    let sac = Sac::read_header_from(&mut cursor, Endian::Little).unwrap();
+
+    assert_eq!(cursor.position(), 632);
+    assert_eq!(sac.npts, 1000);
+    assert_eq!(sac.kstnm, "CDV");
+    assert!(sac.first.is_empty());
+}
+
+#[test]
+fn normalize_rms() {
+    let path = Path::new("tests/test.sac");
+    let mut sac = Sac::from_file(path, Endian::Little).unwrap();
+    sac.normalize_rms(1.0).unwrap();
+
+    let y = &sac.first;
+    let rms = (y.iter().map(|v| v * v).sum::<f32>() / y.len() as f32).sqrt();
+    assert!((rms - 1.0).abs() < 1e-4);
+
+    let max = y.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    assert_eq!(sac.depmax, max);
+}
+
+#[test]
+fn update_mean() {
+    let path = Path::new("tests/test.sac");
+    let mut sac = Sac::from_file(path, Endian::Little).unwrap();
+    sac.first.iter_mut().for_each(|v| *v += 1.0);
+    sac.update_mean();
+
+    let mean = sac.first.iter().map(|v| *v as f64).sum::<f64>() / sac.first.len() as f64;
+    assert_eq!(sac.depmen, mean as f32);
+}
+
+#[test]
+fn hash() {
+    let path = Path::new("tests/test.sac");
+    let mut set = HashSet::new();
+    set.insert(Sac::from_file(path, Endian::Little).unwrap());
+    set.insert(Sac::from_file(path, Endian::Little).unwrap());
+    assert_eq!(set.len(), 1);
+
+    let mut sac = Sac::from_file(path, Endian::Little).unwrap();
+    sac.first[0] = f32::NAN;
+    set.insert(sac);
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn trim_to_common() {
+    let path = Path::new("tests/test.sac");
+    let mut traces: Vec<Sac> = (0..3)
+        .map(|i| {
+            let mut sac = Sac::from_file(path, Endian::Little).unwrap();
+            sac.b += i as f32;
+            sac
+        })
+        .collect();
+
+    Sac::trim_to_common(&mut traces).unwrap();
+    for sac in &traces {
+        assert_eq!(sac.npts, 800);
+        assert_eq!(sac.first.len(), 800);
+        assert!((sac.b - traces[0].b).abs() < 1e-4);
+    }
+
+    traces[0].b += 100.0;
+    assert!(Sac::trim_to_common(&mut traces).is_err());
+}
+
+#[test]
+fn trim_to_common_error_keeps_data() {
+    let path = Path::new("tests/test.sac");
+    let sac = Sac::from_file(path, Endian::Little).unwrap();
+    let mut coarse = sac.clone();
+    coarse.delta *= 2.0;
+    coarse.b += 1.0;
+
+    let mut traces = vec![sac, coarse];
+    let before = traces.clone();
+    assert!(Sac::trim_to_common(&mut traces).is_err());
+    assert_eq!(traces, before);
+}
+
+#[test]
+fn validate_ascii() {
+    let mut sac = Sac::new();
+    sac.validate_ascii().unwrap();
+
+    sac.kstnm = "CDÉ".to_owned();
+    assert!(sac.validate_ascii().is_err());
+}
+
+#[test]
+fn trim_keeps_picks() {
+    let path = Path::new("tests/test.sac");
+    let sac = Sac::from_file(path, Endian::Little).unwrap();
+    let index = ((sac.a - sac.b) / sac.delta).round() as usize;
+    let value = sac.first[index];
+
+    let mut shifted = Sac::from_file(path, Endian::Little).unwrap();
+    shifted.b += 0.5;
+    let mut traces = vec![sac, shifted];
+    Sac::trim_to_common(&mut traces).unwrap();
+
+    let sac = &traces[0];
+    let index = ((sac.a - sac.b) / sac.delta).round() as usize;
+    assert_eq!(sac.a, 10.464);
+    assert_eq!(sac.first[index], value);
+}
+
+#[test]
+fn empty_file() {
+    let path = Path::new("tests/test_empty.sac");
+    fs::write(path, []).unwrap();
+
+    let err = Sac::from_file(path, Endian::Little).unwrap_err();
+    assert!(matches!(
+        err,
+        SacError::TruncatedHeader {
+            expected: 632,
+            found: 0
+        }
+    ));
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn pick() {
+    let mut sac = Sac::new();
+    assert_eq!(sac.pick(3), None);
+    assert_eq!(sac.pick(10), None);
+
+    sac.set_pick(3, 12.5, "P").unwrap();
+    assert_eq!(sac.pick(3), Some((12.5, "P")));
+    assert_eq!(sac.t[3], 12.5);
+    assert_eq!(sac.kt[3], "P");
+}
+
+#[test]
+fn str_path() {
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    sac.to_file("tests/test_str_path.sac", Endian::Little)
+        .unwrap();
+
+    let sac = Sac::from_file(String::from("tests/test_str_path.sac"), Endian::Little).unwrap();
+    assert_eq!(sac.npts, 1000);
+
+    fs::remove_file("tests/test_str_path.sac").unwrap();
+}
+
+#[test]
+fn merge_all() {
+    let path = Path::new("tests/test.sac");
+    let sac = Sac::from_file(path, Endian::Little).unwrap();
+
+    let segments: Vec<Sac> = (0..3)
+        .rev()
+        .map(|i| {
+            let mut segment = Sac::from_file(path, Endian::Little).unwrap();
+            segment.first = sac.first[i * 300..(i + 1) * 300].to_vec();
+            segment.b = sac.b + (i * 300) as f32 * sac.delta;
+            segment
+        })
+        .collect();
+
+    let merged = Sac::merge_all(segments).unwrap();
+    assert_eq!(merged.npts, 900);
+    assert_eq!(merged.b, sac.b);
+    assert_eq!(merged.first, sac.first[..900]);
+
+    let overlapping = vec![
+        Sac::from_file(path, Endian::Little).unwrap(),
+        Sac::from_file(path, Endian::Little).unwrap(),
+    ];
+    assert!(Sac::merge_all(overlapping).is_err());
+}
+
+#[test]
+fn clone_error() {
+    let err = Sac::from_slice(&[0; 16], Endian::Little).unwrap_err();
+    let cloned = err.clone();
+    assert_eq!(err.to_string(), cloned.to_string());
+}
+
+fn assert_header_preserved(before: &mut Sac, after: &Sac) {
+    before.depmin = after.depmin;
+    before.depmax = after.depmax;
+    before.depmen = after.depmen;
+    assert!(**before == **after);
+}
+
+#[test]
+fn ops_preserve_header() {
+    let path = Path::new("tests/test.sac");
+
+    let mut before = Sac::from_file(path, Endian::Little).unwrap();
+    let mut after = Sac::from_file(path, Endian::Little).unwrap();
+    after.update_extrema();
+    after.update_mean();
+    assert_header_preserved(&mut before, &after);
+
+    let mut before = Sac::from_file(path, Endian::Little).unwrap();
+    let mut after = Sac::from_file(path, Endian::Little).unwrap();
+    after.scale = 2.0;
+    after.normalize_rms(1.0).unwrap();
+    before.scale = after.scale;
+    assert_header_preserved(&mut before, &after);
+    assert!(after.leven);
+    assert_eq!(after.iftype, SacFileType::Time);
+
+    let mut before = Sac::from_file(path, Endian::Little).unwrap();
+    let mut traces = vec![
+        Sac::from_file(path, Endian::Little).unwrap(),
+        Sac::from_file(path, Endian::Little).unwrap(),
+    ];
+    traces[1].b += 1.0;
+    Sac::trim_to_common(&mut traces).unwrap();
+    let after = &traces[0];
+    before.b = after.b;
+    before.npts = after.npts;
+    before.e = after.e;
+    assert_header_preserved(&mut before, after);
+}
+
+#[test]
+fn decimate_preserves_header() {
+    let mut before = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    for i in 0..10 {
+        before.t[i] = before.b + i as f32;
+    }
+
+    let mut after = before.clone();
+    after.decimate(3).unwrap();
+    assert_eq!(after.delta, before.delta * 3.0);
+    assert_eq!(after.npts, 334);
+    assert_eq!(after.e, after.b + 333.0 * after.delta);
+
+    // Reference time, begin and picks are untouched, so every pick still
+    // names the same absolute time
+    assert_eq!(
+        (after.nzyear, after.nzjday, after.nzhour),
+        (before.nzyear, before.nzjday, before.nzhour)
+    );
+    assert_eq!(
+        (after.nzmin, after.nzsec, after.nzmsec),
+        (before.nzmin, before.nzsec, before.nzmsec)
+    );
+    assert_eq!(after.b, before.b);
+    assert_eq!(after.t, before.t);
+
+    before.delta = after.delta;
+    before.npts = after.npts;
+    before.e = after.e;
+    assert_header_preserved(&mut before, &after);
+}
+
+#[test]
+fn next_fast_len() {
+    assert_eq!(dsp::next_fast_len(0), Some(1));
+    assert_eq!(dsp::next_fast_len(7), Some(8));
+    assert_eq!(dsp::next_fast_len(1000), Some(1000));
+    assert_eq!(dsp::next_fast_len(1001), Some(1024));
+    assert_eq!(dsp::next_fast_len(1025), Some(1080));
+
+    let smooth = |mut v: usize| {
+        for p in [2, 3, 5] {
+            while v.is_multiple_of(p) {
+                v /= p;
+            }
+        }
+        v == 1
+    };
+    for n in 1..5000 {
+        let expected = (n..).find(|v| smooth(*v));
+        assert_eq!(dsp::next_fast_len(n), expected, "{}", n);
+    }
+
+    // Large inputs return quickly, and past the last fast length there is none
+    let last = 1usize << (usize::BITS - 1);
+    assert_eq!(dsp::next_fast_len(last), Some(last));
+    assert_eq!(dsp::next_fast_len(last + 1).map(|v| v > last), Some(true));
+    assert_eq!(dsp::next_fast_len(usize::MAX - 1), None);
+    assert_eq!(dsp::next_fast_len(usize::MAX), None);
+}
+
+#[test]
+fn to_file_mkdir() {
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    let path = Path::new("tests/test_mkdir/sub/dir/test.sac");
+    assert!(sac.to_file(path, Endian::Little).is_err());
+
+    sac.to_file_mkdir(path, Endian::Little).unwrap();
+    let sac = Sac::from_file(path, Endian::Little).unwrap();
+    assert_eq!(sac.npts, 1000);
+
+    fs::remove_dir_all("tests/test_mkdir").unwrap();
+}
+
+#[test]
+fn components() {
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    match sac.components() {
+        Components::Single(y) => assert_eq!(y.len(), 1000),
+        _ => panic!("expected a single component"),
+    }
+
+    let mut sac = Sac::new();
+    sac.iftype = SacFileType::RealImag;
+    sac.first = vec![1.0, 2.0];
+    sac.second = vec![3.0, 4.0];
+    match sac.components() {
+        Components::Pair { a, b, kind } => {
+            assert_eq!(a, [1.0, 2.0]);
+            assert_eq!(b, [3.0, 4.0]);
+            assert_eq!(kind, SpectralKind::RealImag);
+        }
+        _ => panic!("expected a pair of components"),
+    }
+}
+
+#[test]
+fn update_extrema_nan() {
+    let mut sac = Sac::new();
+    sac.first = vec![1.0, 4.0, f32::NAN, -2.0, f32::INFINITY];
+    sac.update_extrema();
+
+    assert_eq!(sac.depmin, -2.0);
+    assert_eq!(sac.depmax, 4.0);
+    assert_eq!(sac.depmen, 1.0);
+}
+
+#[test]
+fn header_only() {
+    let src = fs::read("tests/test.sac").unwrap();
+    let sac = Sac::from_slice(&src[..632], Endian::Little).unwrap();
+
+    assert_eq!(sac.npts, 1000);
+    assert_eq!(sac.kstnm, "CDV");
+    assert!(sac.first.is_empty());
+    assert!(sac.second.is_empty());
+}
+
+#[test]
+fn apply_scale() {
+    let path = Path::new("tests/test.sac");
+    let raw = Sac::from_file(path, Endian::Little).unwrap();
+    let mut sac = Sac::from_file(path, Endian::Little).unwrap();
+
+    sac.scale = -12345.0;
+    sac.apply_scale().unwrap();
+    assert_eq!(sac.first, raw.first);
+
+    sac.scale = 2.0;
+    sac.apply_scale().unwrap();
+    assert_eq!(sac.scale, 1.0);
+    for (a, b) in sac.first.iter().zip(&raw.first) {
+        assert_eq!(*a, b * 2.0);
+    }
+}
+
+fn mean(y: &[f32]) -> f32 {
+    y.iter().sum::<f32>() / y.len() as f32
+}
+
+#[test]
+fn ops_refresh_depmen() {
+    let path = Path::new("tests/test.sac");
+
+    let mut traces = vec![
+        Sac::from_file(path, Endian::Little).unwrap(),
+        Sac::from_file(path, Endian::Little).unwrap(),
+    ];
+    traces[1].b += 5.0;
+    Sac::trim_to_common(&mut traces).unwrap();
+    for sac in &traces {
+        assert!((sac.depmen - mean(&sac.first)).abs() < 1e-6);
+    }
+
+    let sac = Sac::from_file(path, Endian::Little).unwrap();
+    let mut second = Sac::from_file(path, Endian::Little).unwrap();
+    second.b = sac.b + sac.first.len() as f32 * sac.delta;
+    second.first.iter_mut().for_each(|v| *v += 1.0);
+    let merged = Sac::merge_all(vec![sac, second]).unwrap();
+    assert!((merged.depmen - mean(&merged.first)).abs() < 1e-6);
+
+    let mut sac = Sac::from_file(path, Endian::Little).unwrap();
+    sac.normalize_rms(3.0).unwrap();
+    assert!((sac.depmen - mean(&sac.first)).abs() < 1e-6);
+}
+
+#[test]
+fn from_slice_counted() {
+    let mut src = fs::read("tests/test.sac").unwrap();
+    let length = src.len();
+    src.extend_from_within(..);
+
+    let (sac, count) = Sac::from_slice_counted(&src, Endian::Little).unwrap();
+    assert_eq!(count, 632 + 4 * sac.npts as usize);
+    assert_eq!(count, length);
+
+    let (next, _) = Sac::from_slice_counted(&src[count..], Endian::Little).unwrap();
+    assert!(next == sac);
+}
+
+#[cfg(unix)]
+#[test]
+fn to_file_keeps_mode() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = Path::new("tests/test_mode.sac");
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    sac.to_file(path, Endian::Little).unwrap();
+    fs::set_permissions(path, fs::Permissions::from_mode(0o640)).unwrap();
+
+    sac.to_file(path, Endian::Little).unwrap();
+    let mode = fs::metadata(path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn detrend_window() {
+    let mut sac = Sac::new();
+    sac.iftype = SacFileType::Time;
+    sac.leven = true;
+    sac.b = 0.0;
+    sac.delta = 1.0;
+    sac.first = (0..200)
+        .map(|i| 3.0 + 0.5 * i as f32 + if i >= 100 { 10.0 } else { 0.0 })
+        .collect();
+
+    sac.detrend_window(0.0, 99.0).unwrap();
+    assert!(sac.first[..100].iter().all(|v| v.abs() < 1e-3));
+    assert!(sac.first[100..].iter().all(|v| (v - 10.0).abs() < 1e-3));
+
+    assert!(sac.detrend_window(-1.0, 50.0).is_err());
+    assert!(sac.detrend_window(50.0, 500.0).is_err());
+}
+
+#[test]
+fn set_reference_now() {
+    let mut sac = Sac::new();
+    sac.set_reference_now();
+
+    assert!(sac.nzyear >= 2024 && sac.nzyear < 2200);
+    assert!((1..=366).contains(&sac.nzjday));
+    assert!((0..24).contains(&sac.nzhour));
+    assert!((0..60).contains(&sac.nzmin));
+    assert!((0..60).contains(&sac.nzsec));
+    assert!((0..1000).contains(&sac.nzmsec));
+}
+
+#[test]
+fn clip() {
+    let path = Path::new("tests/test.sac");
+    let mut sac = Sac::from_file(path, Endian::Little).unwrap();
+    sac.first[500] = 100.0;
+
+    sac.clip(-1.0, 1.0).unwrap();
+    assert!(sac.depmax <= 1.0);
+    assert!(sac.depmin >= -1.0);
+    assert_eq!(sac.first[500], 1.0);
+    assert!(sac.clip(1.0, -1.0).is_err());
+
+    let mut sac = Sac::from_file(path, Endian::Little).unwrap();
+    sac.first[500] = 100.0;
+    sac.clip_sigma(3.0).unwrap();
+    assert!(sac.depmax < 100.0);
+
+    sac.iftype = SacFileType::AmpPhase;
+    assert!(sac.clip(-1.0, 1.0).is_err());
+}
+
+#[test]
+fn spectral_policy() {
+    let mut sac = Sac::new();
+    sac.iftype = SacFileType::RealImag;
+    sac.first = vec![1.0, 2.0];
+    sac.second = vec![3.0, 4.0];
+
+    sac.scale = 2.0;
+    sac.apply_scale().unwrap();
+    assert_eq!(sac.first, [2.0, 4.0]);
+    assert_eq!(sac.second, [6.0, 8.0]);
+
+    assert!(sac.normalize_rms(1.0).is_err());
+    assert!(sac.clip(-1.0, 1.0).is_err());
+    assert!(sac.detrend_window(0.0, 1.0).is_err());
+    assert_eq!(sac.first, [2.0, 4.0]);
+
+    sac.iftype = SacFileType::AmpPhase;
+    sac.scale = 0.5;
+    sac.apply_scale().unwrap();
+    assert_eq!(sac.first, [1.0, 2.0]);
+    assert_eq!(sac.second, [6.0, 8.0]);
+}
+
+#[test]
+fn from_directory() {
+    let err = Sac::from_file("tests", Endian::Little).unwrap_err();
+    match err {
+        SacError::Io(msg) => assert_eq!(msg, "tests: path is a directory"),
+        _ => panic!("expected an I/O error"),
+    }
+}
+
+#[test]
+fn convert_endian_directory() {
+    let dst = Path::new("tests/test_convert_dir.sac");
+    let err = Sac::convert_endian_file("tests", dst, Endian::Little, Endian::Big).unwrap_err();
+    match err {
+        SacError::Io(msg) => assert_eq!(msg, "tests: path is a directory"),
+        _ => panic!("expected an I/O error"),
+    }
+    assert!(!dst.exists());
+}
+
+#[test]
+fn sample_rate_rounded() {
+    let mut sac = Sac::new();
+    assert_eq!(sac.sampling_rate(), None);
+
+    sac.delta = 0.009999999;
+    assert_ne!(sac.sampling_rate(), Some(100.0));
+    assert_eq!(sac.sample_rate_rounded(2), Some(100.0));
+
+    sac.delta = 0.3;
+    assert_eq!(sac.sample_rate_rounded(1), Some(3.3));
+}
+
+#[test]
+fn validate() {
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    assert!(sac.validate().is_empty());
+
+    let mut sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    sac.nvhdr = 5;
+    sac.delta = -1.0;
+    sac.first.pop();
+    sac.stla = 120.0;
+    sac.cmpaz = 400.0;
+
+    let issues = sac.validate();
+    let errors = issues.iter().filter(|i| i.severity == Severity::Error);
+    let warnings = issues.iter().filter(|i| i.severity == Severity::Warning);
+    assert_eq!(errors.count(), 3);
+    assert_eq!(warnings.count(), 2);
+}
+
+#[test]
+fn long_trace_mean() {
+    let mut sac = Sac::new();
+    sac.first = vec![0.1; 1_000_000];
+
+    let naive = sac.first.iter().sum::<f32>() / sac.first.len() as f32;
+    assert!((naive - 0.1).abs() > 1e-4);
+
+    sac.update_extrema();
+    assert_eq!(sac.depmen, 0.1);
+    sac.update_mean();
+    assert_eq!(sac.depmen, 0.1);
+}
+
+#[test]
+fn from_xy_traces() {
+    let mut x = Sac::new();
+    x.first = (0..10).map(|i| i as f32 * 0.5).collect();
+    let mut y = Sac::new();
+    y.first = x.first.iter().map(|v| v * v).collect();
+    y.kstnm = "XY".to_owned();
+
+    let sac = Sac::from_xy_traces(&x, &y).unwrap();
+    let bytes = sac.to_slice(Endian::Big).unwrap();
+    let sac = Sac::from_slice(&bytes, Endian::Big).unwrap();
+
+    assert_eq!(sac.iftype, SacFileType::XY);
+    assert_eq!(sac.npts, 10);
+    assert_eq!(sac.kstnm, "XY");
+    assert_eq!(
+        sac.components(),
+        Components::Xy {
+            x: &x.first,
+            y: &y.first
+        }
+    );
+
+    y.first.pop();
+    assert!(Sac::from_xy_traces(&x, &y).is_err());
+}
+
+#[test]
+fn content_hash() {
+    let path = Path::new("tests/test.sac");
+    let a = Sac::from_file(path, Endian::Little).unwrap();
+    let mut b = Sac::from_file(path, Endian::Little).unwrap();
+    b.first.iter_mut().for_each(|v| *v *= 2.0);
+    assert_eq!(a.content_hash(), b.content_hash());
+
+    b.kstnm = "VDC".to_owned();
+    assert_ne!(a.content_hash(), b.content_hash());
+}
+
+#[test]
+fn short_header() {
+    let src = fs::read("tests/test.sac").unwrap();
+    let err = Sac::from_slice(&src[..100], Endian::Little).unwrap_err();
+    assert!(matches!(
+        err,
+        SacError::TruncatedHeader {
+            expected: 632,
+            found: 100
+        }
+    ));
+}
+
+#[test]
+fn merge_tolerance() {
+    let path = Path::new("tests/test.sac");
+    let sac = Sac::from_file(path, Endian::Little).unwrap();
+    let segment = |i: usize, offset: f32| {
+        let mut segment = Sac::from_file(path, Endian::Little).unwrap();
+        segment.first = sac.first[i * 500..(i + 1) * 500].to_vec();
+        segment.b = sac.b + (i as f32 * 500.0 + offset) * sac.delta;
+        segment
+    };
+
+    let merged = Sac::merge_all(vec![segment(0, 0.0), segment(1, 0.3)]).unwrap();
+    assert_eq!(merged.first, sac.first);
+
+    assert!(Sac::merge_all(vec![segment(0, 0.0), segment(1, 0.7)]).is_err());
+    let merged = Sac::merge_all_within(vec![segment(0, 0.0), segment(1, 0.7)], 1.0).unwrap();
+    assert_eq!(merged.npts, 1000);
+}
+
+#[test]
+fn failure_convention() {
+    let mut sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+
+    // No-op: nothing to do succeeds
+    let mut empty = Sac::new();
+    assert!(empty.normalize_rms(1.0).is_ok());
+    assert!(Sac::trim_to_common(&mut []).is_ok());
+
+    // Precondition violated: Err, data untouched
+    let before = sac.first.clone();
+    assert!(sac.clip(1.0, -1.0).is_err());
+    assert!(sac.detrend_window(0.0, 1.0).is_err());
+    assert_eq!(sac.first, before);
+
+    for target in [f32::NAN, f32::INFINITY, -1.0] {
+        assert!(sac.normalize_rms(target).is_err());
+        assert!(empty.normalize_rms(target).is_err());
+    }
+    assert!(Sac::merge_all(vec![]).is_err());
+    assert_eq!(sac.first, before);
+
+    sac.iftype = SacFileType::AmpPhase;
+    assert!(sac.normalize_rms(1.0).is_err());
+    assert_eq!(sac.first, before);
+}
+
+#[test]
+fn failure_convention_out_of_bounds() {
+    let mut sac = Sac::new();
+    assert!(sac.set_pick(10, 1.0, "P").is_err());
+    assert_eq!(sac.pick(10), None);
+    assert_eq!(sac.t, Sac::new().t);
+}
+
+#[test]
+fn from_reader_progress() {
+    let src = std::fs::read("tests/test.sac").unwrap();
+    let mut calls = Vec::new();
+    let sac = Sac::from_reader_progress(&mut Cursor::new(&src), Endian::Little, |n, total| {
+        calls.push((n, total))
+    })
+    .unwrap();
+
+    assert_eq!(sac.npts, 1000);
+    assert!(!calls.is_empty());
+    assert_eq!(calls.last(), Some(&(src.len() as u64, None)));
+}
+
+#[test]
+fn from_file_progress() {
+    let len = fs::metadata("tests/test.sac").unwrap().len();
+    let mut calls = Vec::new();
+    let sac = Sac::from_file_progress("tests/test.sac", Endian::Little, |n, total| {
+        calls.push((n, total))
+    })
+    .unwrap();
+
+    assert_eq!(sac.npts, 1000);
+    assert!(calls.iter().all(|(_, total)| *total == Some(len)));
+    let (done, total) = *calls.last().unwrap();
+    assert_eq!(Some(done), total);
+}
+
+#[test]
+fn stats_window() {
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+
+    let all = sac.stats().unwrap();
+    assert_eq!(all.min, sac.depmin);
+    assert_eq!(all.max, sac.depmax);
+
+    let start = sac.b + 100.0 * sac.delta;
+    let end = sac.b + 199.0 * sac.delta;
+    let stats = sac.stats_window(start, end).unwrap();
+
+    let window = &sac.first[100..200];
+    let mean = window.iter().map(|v| *v as f64).sum::<f64>() / 100.0;
+    let rms = (window.iter().map(|v| *v as f64 * *v as f64).sum::<f64>() / 100.0).sqrt();
+    assert_eq!(
+        stats.min,
+        window.iter().cloned().fold(f32::INFINITY, f32::min)
+    );
+    assert_eq!(
+        stats.max,
+        window.iter().cloned().fold(f32::NEG_INFINITY, f32::max)
+    );
+    assert!((stats.mean as f64 - mean).abs() < 1e-6);
+    assert!((stats.rms as f64 - rms).abs() < 1e-6);
+
+    assert!(sac.stats_window(sac.b - 1.0, end).is_err());
+    assert!(sac.stats_window(start, sac.e + 1.0).is_err());
+}
+
+#[test]
+fn to_file_synced() {
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    let path = Path::new("tests/test_synced.sac");
+    sac.to_file_synced(path, Endian::Little).unwrap();
+
+    let synced = Sac::from_file(path, Endian::Little).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(synced, sac);
+}
+
+#[test]
+fn file_type_raw() {
+    assert_eq!(SacFileType::Unknown(52).raw(), 52);
+    assert_eq!(SacFileType::Time.raw(), 1);
+    assert_eq!(SacFileType::XY.raw(), 4);
+}
+
+#[test]
+fn from_reader() {
+    let src = std::fs::read("tests/test.sac").unwrap();
+    let sac = Sac::from_reader(&mut Cursor::new(&src), Endian::Little).unwrap();
+    assert_eq!(
+        sac,
+        Sac::from_file("tests/test.sac", Endian::Little).unwrap()
+    );
+
+    let err = Sac::from_reader(&mut &src[..100], Endian::Little).unwrap_err();
+    assert!(matches!(
+        err,
+        SacError::TruncatedHeader {
+            expected: 632,
+            found: 100
+        }
+    ));
+}
+
+#[test]
+fn to_writer() {
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+
+    let mut dst = Vec::new();
+    sac.to_writer(&mut dst, Endian::Big).unwrap();
+    assert_eq!(dst, sac.to_slice(Endian::Big).unwrap());
+
+    let mut buffered = std::io::BufWriter::new(Vec::new());
+    sac.to_writer(&mut buffered, Endian::Big).unwrap();
+    assert_eq!(buffered.get_ref(), &dst);
+}
+
+#[test]
+fn merge_fill_report() {
+    let path = Path::new("tests/test.sac");
+    let sac = Sac::from_file(path, Endian::Little).unwrap();
+    let segment = |start: usize, end: usize, at: f32| {
+        let mut segment = Sac::from_file(path, Endian::Little).unwrap();
+        segment.first = sac.first[start..end].to_vec();
+        segment.b = sac.b + at * sac.delta;
+        segment
+    };
+
+    // 10 samples missing between the halves
+    let segments = vec![segment(0, 500, 0.0), segment(500, 1000, 510.0)];
+    let (merged, report) = Sac::merge_fill(segments, 0.0).unwrap();
+    assert_eq!(merged.npts, 1010);
+    assert_eq!(&merged.first[500..510], &[0.0; 10]);
+    assert_eq!(report.junctions.len(), 1);
+    assert!((report.junctions[0].gap_samples - 10.0).abs() < 0.01);
+    assert!((report.junctions[0].gap_seconds - 0.1).abs() < 1e-4);
+    assert_eq!(report.junctions[0].filled, 10);
+
+    // 10 samples covered by both segments
+    let segments = vec![segment(0, 500, 0.0), segment(490, 1000, 490.0)];
+    let (merged, report) = Sac::merge_fill(segments, 0.0).unwrap();
+    assert_eq!(merged.first, sac.first);
+    assert!((report.junctions[0].gap_samples + 10.0).abs() < 0.01);
+    assert_eq!(report.junctions[0].trimmed, 10);
+}
+
+#[test]
+fn auto_endian() {
+    let little = std::fs::read("tests/test.sac").unwrap();
+    let sac = Sac::from_slice(&little, Endian::Little).unwrap();
+    let big = sac.to_slice(Endian::Big).unwrap();
+
+    assert!(matches!(Sac::detect_endian(&little), Ok(Endian::Little)));
+    assert!(matches!(Sac::detect_endian(&big), Ok(Endian::Big)));
+    assert_eq!(Sac::from_slice_auto(&big).unwrap().0, sac);
+
+    let (little_sac, endian) = Sac::from_file_auto("tests/test.sac").unwrap();
+    assert_eq!(little_sac, sac);
+    assert_eq!(endian, Endian::Little);
+
+    let (big_sac, endian) = Sac::from_file_auto("tests/test_be.sac").unwrap();
+    assert_eq!(big_sac, sac);
+    assert!(matches!(endian, Endian::Big));
+    assert_eq!(
+        big_sac.to_slice(endian).unwrap(),
+        fs::read("tests/test_be.sac").unwrap()
+    );
+
+    let mut garbage = little.clone();
+    garbage[304..308].copy_from_slice(&[0xff; 4]);
+    let err = Sac::from_slice_auto(&garbage).unwrap_err();
+    assert!(err.to_string().contains("little endian"));
+}
+
+#[test]
+fn version_7_footer() {
+    let mut sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    assert!(sac.footer().is_none());
+
+    sac.nvhdr = 7;
+    let mut src = sac.to_slice(Endian::Little).unwrap();
+    assert_eq!(src.len(), 632 + 1000 * 4 + 22 * 8);
+
+    // A begin time that f32 can't hold exactly
+    let b = sac.b as f64 + 1e-9;
+    let offset = src.len() - 22 * 8 + 8;
+    src[offset..offset + 8].copy_from_slice(&b.to_le_bytes());
+
+    let (v7, count) = Sac::from_slice_counted(&src, Endian::Little).unwrap();
+    assert_eq!(count, src.len());
+    assert_eq!(v7.b, sac.b);
+    assert_eq!(v7.footer().unwrap().b, b);
+    assert_eq!(v7.first, sac.first);
+    assert_eq!(v7.to_slice(Endian::Little).unwrap(), src);
+
+    let big = v7.to_slice(Endian::Big).unwrap();
+    assert_eq!(Sac::from_slice(&big, Endian::Big).unwrap(), v7);
+
+    let mut edited = Sac::from_slice(&src, Endian::Little).unwrap();
+    edited.b = 1.0;
+    assert_eq!(edited.footer().unwrap().b, 1.0);
+}
+
+#[test]
+fn write_version() {
+    let mut sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    sac.nvhdr = 7;
+    let mut src = sac.to_slice(Endian::Little).unwrap();
+    let b = sac.b as f64 + 1e-9;
+    let offset = src.len() - 22 * 8 + 8;
+    src[offset..offset + 8].copy_from_slice(&b.to_le_bytes());
+    let v7 = Sac::from_slice(&src, Endian::Little).unwrap();
+
+    let path = Path::new("tests/test_version_6.sac");
+    v7.to_file_version(path, Endian::Little, 6).unwrap();
+    assert_eq!(fs::metadata(path).unwrap().len(), 632 + 1000 * 4);
+
+    // The footer is gone, and with it the double-precision begin time
+    let v6 = Sac::from_file(path, Endian::Little).unwrap();
+    assert_eq!(v6.nvhdr, 6);
+    assert!(v6.footer().is_none());
+    assert_eq!(v6.b, v7.b);
+    assert_eq!(v6.first, v7.first);
+    assert_eq!(v7.nvhdr, 7);
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(v7.to_slice_version(Endian::Little, 7).unwrap(), src);
+    assert!(v7.to_slice_version(Endian::Little, 5).is_err());
+    assert!(v7.to_file_version(path, Endian::Little, 8).is_err());
+    assert!(!path.exists());
+}
+
+#[test]
+fn dep_type() {
+    let src = std::fs::read("tests/test.sac").unwrap();
+    let sac = Sac::from_slice(&src, Endian::Little).unwrap();
+    assert_eq!(sac.idep, SacDepType::Volts);
+    assert_eq!(sac.to_slice(Endian::Little).unwrap(), src);
+
+    assert_eq!(SacDepType::from(7), SacDepType::Velocity);
+    assert_eq!(SacDepType::from(-12345), SacDepType::Unknown(-12345));
+    assert_eq!(i32::from(SacDepType::Unknown(99)), 99);
+}
+
+#[test]
+fn strict_data_length() {
+    let mut sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    let header_only = &sac.to_slice(Endian::Little).unwrap()[..632];
+    assert!(Sac::from_slice(header_only, Endian::Little).is_ok());
+
+    for iftype in [
+        SacFileType::Time,
+        SacFileType::RealImag,
+        SacFileType::AmpPhase,
+        SacFileType::XY,
+    ] {
+        sac.iftype = iftype;
+        sac.second = match iftype {
+            SacFileType::Time => vec![],
+            _ => sac.first.clone(),
+        };
+
+        let src = sac.to_slice(Endian::Little).unwrap();
+        assert!(Sac::from_slice(&src, Endian::Little).is_ok());
+
+        let short = &src[..src.len() - 4];
+        let err = Sac::from_slice(short, Endian::Little).unwrap_err();
+        assert!(err.to_string().contains("Data length mismatch"));
+
+        let mut long = src.clone();
+        long.extend_from_slice(&[0; 4]);
+        assert!(Sac::from_slice(&long, Endian::Little).is_err());
+        let (_, count) = Sac::from_slice_counted(&long, Endian::Little).unwrap();
+        assert_eq!(count, src.len());
+    }
+}
+
+#[test]
+fn zero_type() {
+    let src = std::fs::read("tests/test.sac").unwrap();
+    let mut sac = Sac::from_slice(&src, Endian::Little).unwrap();
+    assert_eq!(sac.iztype, SacZeroType::Begin);
+    assert_eq!(sac.to_slice(Endian::Little).unwrap(), src);
+
+    for code in [5, 9, 10, 11, 12, 13, 17, 22, 23, -12345] {
+        assert_eq!(SacZeroType::from(code).raw(), code);
+    }
+    assert_eq!(SacZeroType::from(15), SacZeroType::Pick(2));
+
+    sac.iztype = SacZeroType::Pick(3);
+    let src = sac.to_slice(Endian::Little).unwrap();
+    let sac = Sac::from_slice(&src, Endian::Little).unwrap();
+    assert_eq!(sac.iztype, SacZeroType::Pick(3));
+
+    assert_eq!(SacZeroType::pick(9), Some(SacZeroType::Pick(9)));
+    assert_eq!(SacZeroType::pick(9).unwrap().raw(), 22);
+    assert_eq!(SacZeroType::pick(10), None);
+}
+
+#[test]
+fn compute_dist_az() {
+    let close = |a: f32, b: f32| (a - b).abs() < 0.01;
+    let mut sac = Sac::new();
+    sac.lcalda = true;
+
+    // Undefined coordinates leave the fields alone
+    sac.compute_dist_az();
+    assert_eq!(sac.gcarc, -12345.0);
+
+    for (ev, st, gcarc, az, baz) in [
+        ((0.0, 0.0), (0.0, 90.0), 90.0, 90.0, 270.0),
+        ((0.0, 0.0), (90.0, 0.0), 90.0, 0.0, 180.0),
+        ((10.0, 20.0), (-10.0, 20.0), 20.0, 180.0, 0.0),
+        // Tokyo to San Francisco
+        (
+            (35.6762, 139.6503),
+            (37.7749, -122.4194),
+            74.415,
+            54.365,
+            303.356,
+        ),
+    ] {
+        (sac.evla, sac.evlo) = ev;
+        (sac.stla, sac.stlo) = st;
+        sac.compute_dist_az();
+        assert!(close(sac.gcarc, gcarc), "gcarc = {}", sac.gcarc);
+        assert!(close(sac.az, az), "az = {}", sac.az);
+        assert!(close(sac.baz, baz), "baz = {}", sac.baz);
+        assert!((sac.dist - gcarc.to_radians() * 6371.0).abs() < 2.0);
+    }
+
+    sac.lcalda = false;
+    sac.stla = 0.0;
+    sac.compute_dist_az();
+    assert!(close(sac.gcarc, 74.415));
+}
+
+#[test]
+fn to_slice_refreshes_extrema() {
+    let mut sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    sac.first[0] = 100.0;
+    sac.first[1] = -100.0;
+    let expected = sac.first.iter().map(|v| *v as f64).sum::<f64>() / 1000.0;
+
+    let src = sac.to_slice(Endian::Little).unwrap();
+    let written = Sac::from_slice(&src, Endian::Little).unwrap();
+    assert_eq!(written.depmax, 100.0);
+    assert_eq!(written.depmin, -100.0);
+    assert!((written.depmen as f64 - expected).abs() < 1e-6);
+
+    // The in-memory header is left alone, and the raw writers opt out
+    assert_ne!(sac.depmax, 100.0);
+    let src = sac.to_slice_raw(Endian::Little).unwrap();
+    let raw = Sac::from_slice(&src, Endian::Little).unwrap();
+    assert_eq!(raw.depmax, sac.depmax);
+
+    let path = Path::new("tests/test_raw.sac");
+    sac.to_file_raw(path, Endian::Little).unwrap();
+    assert_eq!(fs::read(path).unwrap(), src);
+    fs::remove_file(path).unwrap();
+}
+
+struct SlowReader<'a> {
+    src: &'a [u8],
+}
+
+impl std::io::Read for SlowReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        std::thread::sleep(Duration::from_millis(5));
+        let n = buf.len().min(64).min(self.src.len());
+        buf[..n].copy_from_slice(&self.src[..n]);
+        self.src = &self.src[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn from_reader_deadline() {
+    let src = std::fs::read("tests/test.sac").unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(60);
+    let sac = Sac::from_reader_deadline(&mut Cursor::new(&src), Endian::Little, deadline);
+    assert!(sac.is_ok());
+
+    let deadline = Instant::now() + Duration::from_millis(20);
+    let err = Sac::from_reader_deadline(&mut SlowReader { src: &src }, Endian::Little, deadline)
+        .unwrap_err();
+    assert!(matches!(err, SacError::Io(msg) if msg == "read timed out"));
+}
+
+#[test]
+fn sync_lengths() {
+    let mut sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    sac.first.extend_from_slice(&[0.0; 100]);
+
+    let src = sac.to_slice(Endian::Little).unwrap();
+    let written = Sac::from_slice(&src, Endian::Little).unwrap();
+    assert_eq!(written.npts, 1100);
+    assert_eq!(written.e, sac.b + 1099.0 * sac.delta);
+    assert_eq!(sac.npts, 1000);
+
+    sac.sync_lengths().unwrap();
+    assert_eq!(sac.npts, 1100);
+    assert_eq!(sac.e, written.e);
+
+    // A version 7 footer read from a file must not bring back the old e
+    let mut v7 = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    v7.nvhdr = 7;
+    let src = v7.to_slice(Endian::Little).unwrap();
+    let mut v7 = Sac::from_slice(&src, Endian::Little).unwrap();
+    v7.first.extend_from_slice(&[0.0; 100]);
+
+    let src = v7.to_slice(Endian::Little).unwrap();
+    let written = Sac::from_slice(&src, Endian::Little).unwrap();
+    assert_eq!(written.npts, 1100);
+    assert_eq!(written.e, v7.b + 1099.0 * v7.delta);
+    assert_eq!(written.footer().unwrap().e, written.e as f64);
+}
+
+#[test]
+fn sync_lengths_header_only() {
+    let src = fs::read("tests/test.sac").unwrap();
+    let header = Sac::read_header_from(&mut &src[..], Endian::Little).unwrap();
+    assert!(header.first.is_empty());
+
+    let src = header.to_slice(Endian::Little).unwrap();
+    let written = Sac::from_slice(&src, Endian::Little).unwrap();
+    assert_eq!(written.npts, 1000);
+    assert_eq!(written.e, header.e);
+}
+
+#[test]
+fn channel_code() {
+    let mut sac = Sac::new();
+    for (kcmpnm, code) in [
+        ("BHZ", Some(('B', 'H', 'Z'))),
+        ("HH1", Some(('H', 'H', '1'))),
+        ("-12345", None),
+        ("VERT", None),
+        ("bhz", None),
+        ("", None),
+    ] {
+        sac.kcmpnm = kcmpnm.to_string();
+        assert_eq!(sac.channel_code(), code, "{}", kcmpnm);
+    }
+}
+
+#[test]
+fn clone() {
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    let mut copy = sac.clone();
+    assert_eq!(copy, sac);
+
+    copy.first.iter_mut().for_each(|v| *v *= 2.0);
+    copy.kstnm = "COPY".to_string();
+    assert_ne!(copy.first, sac.first);
+    assert_eq!(sac.kstnm, "CDV");
+}
+
+#[test]
+fn write_into() {
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    let mut other = sac.clone();
+    other.first.iter_mut().for_each(|v| *v += 1.0);
+
+    let mut buf = Vec::new();
+    sac.write_into(&mut buf, Endian::Little).unwrap();
+    assert_eq!(buf, sac.to_slice(Endian::Little).unwrap());
+
+    let (ptr, capacity) = (buf.as_ptr(), buf.capacity());
+    other.write_into(&mut buf, Endian::Little).unwrap();
+    assert_eq!(buf, other.to_slice(Endian::Little).unwrap());
+    assert_eq!(buf.capacity(), capacity);
+    assert_eq!(buf.as_ptr(), ptr);
+}
+
+#[test]
+fn cut_around() {
+    let mut sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    let original = sac.first.clone();
+
+    sac.o = 12.0;
+    sac.cut_around("o", 1.0, 2.0).unwrap();
+    assert_eq!(sac.npts, 301);
+    assert!((sac.b - 11.0).abs() < 1e-4);
+    assert!((sac.e - 14.0).abs() < 1e-4);
+    assert_eq!(sac.first, &original[154..455]);
+
+    assert!(sac.cut_around("t0", 1.0, 1.0).is_err());
+    assert!(sac.cut_around("x", 1.0, 1.0).is_err());
+    assert!(sac.cut_around("o", 5.0, 1.0).is_err());
+    assert_eq!(sac.npts, 301);
+}
+
+#[test]
+fn reference_time() {
+    let mut sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    assert_eq!(sac.reference_time(), Some((1981, 88, 10, 38, 14, 0)));
+
+    sac.set_reference_time((2020, 366, 23, 59, 59, 999))
+        .unwrap();
+    assert_eq!(sac.reference_time(), Some((2020, 366, 23, 59, 59, 999)));
+
+    assert!(sac.set_reference_time((2021, 366, 0, 0, 0, 0)).is_err());
+    assert!(sac.set_reference_time((2021, 0, 0, 0, 0, 0)).is_err());
+    assert!(sac.set_reference_time((2021, 1, 24, 0, 0, 0)).is_err());
+    assert_eq!(sac.nzyear, 2020);
+
+    sac.nzmsec = -12345;
+    assert_eq!(sac.reference_time(), None);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn reference_datetime() {
+    use chrono::NaiveDate;
+
+    let mut sac = Sac::new();
+    assert_eq!(sac.reference_datetime(), None);
+
+    sac.set_reference_time((2020, 366, 23, 59, 59, 999))
+        .unwrap();
+    let expected = NaiveDate::from_ymd_opt(2020, 12, 31).unwrap();
+    assert_eq!(
+        sac.reference_datetime(),
+        expected.and_hms_milli_opt(23, 59, 59, 999)
+    );
+
+    sac.set_reference_time((2021, 365, 0, 0, 0, 0)).unwrap();
+    let expected = NaiveDate::from_ymd_opt(2021, 12, 31).unwrap();
+    assert_eq!(sac.reference_datetime(), expected.and_hms_opt(0, 0, 0));
+
+    sac.nzjday = 366;
+    assert_eq!(sac.reference_datetime(), None);
+}
+
+#[test]
+fn calendar_date() {
+    let mut sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    assert_eq!(sac.calendar_date(), Some((1981, 3, 29)));
+
+    sac.set_calendar_date(2020, 2, 29).unwrap();
+    assert_eq!((sac.nzyear, sac.nzjday), (2020, 60));
+    assert_eq!(sac.calendar_date(), Some((2020, 2, 29)));
+
+    sac.set_calendar_date(2021, 3, 1).unwrap();
+    assert_eq!((sac.nzyear, sac.nzjday), (2021, 60));
+    assert_eq!(sac.calendar_date(), Some((2021, 3, 1)));
+
+    assert!(sac.set_calendar_date(2021, 2, 29).is_err());
+    assert!(sac.set_calendar_date(2021, 13, 1).is_err());
+    assert_eq!(sac.nzjday, 60);
+
+    sac.nzjday = 366;
+    assert_eq!(sac.calendar_date(), None);
+    sac.nzyear = 2020;
+    assert_eq!(sac.calendar_date(), Some((2020, 12, 31)));
+    sac.nzjday = -12345;
+    assert_eq!(sac.calendar_date(), None);
+}
+
+#[test]
+fn merge_across_year_boundary() {
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+
+    // 10 s of data ending at midnight on Dec 31 of a leap year, then 10 s
+    // referenced to the first second of the next year
+    let mut before = sac.clone();
+    before
+        .set_reference_time((2020, 366, 23, 59, 50, 0))
+        .unwrap();
+    before.b = 0.0;
+    let mut after = sac.clone();
+    after.set_reference_time((2021, 1, 0, 0, 0, 0)).unwrap();
+    after.b = 0.0;
+    after.first.iter_mut().for_each(|v| *v += 1.0);
+
+    let expected: Vec<f32> = before.first.iter().chain(&after.first).cloned().collect();
+    let merged = Sac::merge_all(vec![after, before]).unwrap();
+    assert_eq!(merged.first, expected);
+    assert_eq!(merged.reference_time(), Some((2020, 366, 23, 59, 50, 0)));
+}
+
+#[test]
+fn decimate() {
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+
+    let mut half = sac.clone();
+    half.decimate(2).unwrap();
+    assert_eq!(half.npts, 500);
+    assert_eq!(half.delta, sac.delta * 2.0);
+    assert_eq!(half.first[1], sac.first[2]);
+    assert_eq!(half.e, half.b + 499.0 * half.delta);
+
+    let mut third = sac.clone();
+    third.decimate(3).unwrap();
+    assert_eq!(third.npts, 334);
+    assert_eq!(third.first.last(), sac.first.last());
+
+    let mut sac = sac;
+    assert!(sac.decimate(0).is_err());
+    sac.leven = false;
+    assert!(sac.decimate(2).is_err());
+}
+
+#[test]
+fn append() {
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    let mut head = sac.clone();
+    head.first.truncate(500);
+    head.sync_lengths().unwrap();
+    let mut tail = sac.clone();
+    tail.first.drain(..500);
+    tail.b = sac.b + 500.0 * sac.delta;
+    tail.sync_lengths().unwrap();
+
+    let mut merged = head.clone();
+    merged.append(&tail).unwrap();
+    assert_eq!(merged.first, sac.first);
+    assert_eq!(merged.npts, 1000);
+    assert_eq!((merged.depmin, merged.depmax), (sac.depmin, sac.depmax));
+
+    let mut other = tail.clone();
+    other.kcmpnm = "BHN".to_string();
+    assert!(head.clone().append(&other).is_err());
+
+    let mut other = tail.clone();
+    other.b += 10.0 * sac.delta;
+    let err = head.clone().append(&other).unwrap_err();
+    assert!(err.to_string().contains("Gap of 10.00 samples"));
+
+    let mut other = tail.clone();
+    other.delta *= 2.0;
+    assert!(head.clone().append(&other).is_err());
+}
+
+#[test]
+fn map_samples() {
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    let squared: Vec<f32> = sac.first.iter().map(|v| v * v).collect();
+
+    let mut mapped = sac.clone();
+    mapped.map_samples(|v| v * v);
+    assert_eq!(mapped.first, squared);
+    assert_eq!(
+        mapped.depmin,
+        squared.iter().cloned().fold(f32::INFINITY, f32::min)
+    );
+
+    let mut indexed = sac.clone();
+    let mut last = (0, 0.0);
+    indexed.map_samples_indexed(|i, t, v| {
+        last = (i, t);
+        v * v
+    });
+    assert_eq!(indexed.first, squared);
+    assert_eq!(last.0, 999);
+    assert!((last.1 - sac.e).abs() < 1e-4);
+}
+
+#[test]
+fn spectral_conversion() {
+    let mut sac = Sac::new();
+    sac.iftype = SacFileType::RealImag;
+    sac.first = vec![0.0, 1.0, 0.0, -3.0, 2.5];
+    sac.second = vec![0.0, 0.0, 2.0, -4.0, -1.5];
+    let (re, im) = (sac.first.clone(), sac.second.clone());
+
+    sac.to_amp_phase().unwrap();
+    assert_eq!(sac.iftype, SacFileType::AmpPhase);
+    assert_eq!(&sac.first[..4], &[0.0, 1.0, 2.0, 5.0]);
+    assert_eq!(sac.second[0], 0.0);
+    assert!((sac.second[2] - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+
+    sac.to_real_imag().unwrap();
+    assert_eq!(sac.iftype, SacFileType::RealImag);
+    for (a, b) in sac
+        .first
+        .iter()
+        .chain(&sac.second)
+        .zip(re.iter().chain(&im))
+    {
+        assert!((a - b).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    sac.iftype = SacFileType::Time;
+    assert!(sac.to_amp_phase().is_err());
+    sac.iftype = SacFileType::XY;
+    assert!(sac.to_real_imag().is_err());
+}
+
+#[test]
+fn synthetic() {
+    let data: Vec<f32> = (0..100).map(|i| (i as f32 * 0.1).sin()).collect();
+    let sac = Sac::synthetic(data.clone(), 0.05, "SYN", Some((2024, 60, 12, 0, 0, 0))).unwrap();
+    assert!(sac.validate().is_empty());
+    assert_eq!(sac.iztype, SacZeroType::Begin);
+
+    let src = sac.to_slice(Endian::Little).unwrap();
+    let read = Sac::from_slice(&src, Endian::Little).unwrap();
+    assert_eq!(read.kstnm, "SYN");
+    assert_eq!(read.npts, 100);
+    assert_eq!(read.first, data);
+    assert_eq!(read.e, 99.0 * 0.05);
+    assert_eq!(read.calendar_date(), Some((2024, 2, 29)));
+
+    let bare = Sac::synthetic(vec![], 1.0, "", None).unwrap();
+    assert_eq!(bare.npts, 0);
+    assert_eq!(bare.reference_time(), None);
+
+    // An invalid reference time is an error, not a panic
+    assert!(Sac::synthetic(vec![1.0], 1.0, "BAD", Some((2023, 366, 0, 0, 0, 0))).is_err());
+}
+
+#[test]
+fn times() {
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    let times = sac.times();
+    assert_eq!(times.len(), sac.npts as usize);
+    assert_eq!(times[0], sac.b);
+    assert!((times[999] - sac.e).abs() < 1e-4);
+
+    let src = sac.to_slice(Endian::Little).unwrap();
+    let header = Sac::read_header_from(&mut &src[..], Endian::Little).unwrap();
+    assert!(header.times().is_empty());
+
+    let xy = Sac::from_xy_traces(&sac, &sac).unwrap();
+    assert_eq!(xy.times(), sac.first);
+
+    // The axis follows the loaded data, not a stale npts
+    let mut stale = sac.clone();
+    stale.npts = 5;
+    assert_eq!(stale.times().len(), sac.first.len());
+}
+
+#[test]
+fn deterministic_output() {
+    let sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    for endian in [Endian::Little, Endian::Big] {
+        let a = sac.to_slice(endian).unwrap();
+        let b = sac.to_slice(endian).unwrap();
+        let c = sac.clone().to_slice(endian).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a, c);
+    }
+
+    // A fresh header with short strings is padded the same way every time
+    let synthetic = || Sac::synthetic(vec![1.0, 2.0], 1.0, "A", None).unwrap();
+    assert_eq!(
+        synthetic().to_slice(Endian::Little).unwrap(),
+        synthetic().to_slice(Endian::Little).unwrap()
+    );
+}
+
+#[test]
+fn rmean_rtrend() {
+    let mut sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    sac.first.iter_mut().for_each(|v| *v += 5.0);
+    sac.rmean().unwrap();
+    let mean = sac.first.iter().map(|v| *v as f64).sum::<f64>() / 1000.0;
+    assert!(mean.abs() < 1e-6);
+    assert!(sac.depmen.abs() < 1e-6);
+
+    let ramp: Vec<f32> = (0..100).map(|i| 2.0 + 0.5 * i as f32).collect();
+    let mut sac = Sac::synthetic(ramp, 0.1, "RAMP", None).unwrap();
+    sac.rtrend().unwrap();
+    assert!(sac.first.iter().all(|v| v.abs() < 1e-4));
+    assert!(sac.depmax.abs() < 1e-4);
+
+    sac.iftype = SacFileType::XY;
+    assert!(sac.rmean().is_err());
+    sac.iftype = SacFileType::AmpPhase;
+    assert!(sac.rtrend().is_err());
+}
+
+#[test]
+fn taper() {
+    let mut sac = Sac::synthetic(vec![2.0; 100], 0.01, "TAPER", None).unwrap();
+    sac.taper(0.1).unwrap();
+
+    assert_eq!(sac.first[0], 0.0);
+    assert_eq!(sac.first[99], 0.0);
+    assert!((sac.first[5] - 1.0).abs() < 1e-6);
+    assert_eq!(sac.first[4], sac.first[95]);
+    assert!(sac.first[10..90].iter().all(|v| *v == 2.0));
+    assert!(sac.first[..10].windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(sac.depmin, 0.0);
+
+    let before = sac.first.clone();
+    sac.taper(0.0).unwrap();
+    assert_eq!(sac.first, before);
+    assert!(sac.taper(0.6).is_err());
+    assert!(sac.taper(-0.1).is_err());
+    assert!(sac.taper(f32::NAN).is_err());
+}
+
+#[test]
+fn taper_units() {
+    let sac = Sac::synthetic(vec![2.0; 101], 0.01, "TAPER", None).unwrap();
+    for width in [0.0, 0.05, 0.123, 0.25, 0.5] {
+        let mut fraction = sac.clone();
+        fraction.taper_fraction(width).unwrap();
+        let mut samples = sac.clone();
+        let n = (width * sac.first.len() as f64).round() as usize;
+        samples.taper_samples(n.min(50)).unwrap();
+        assert_eq!(fraction, samples, "{}", width);
+    }
+
+    let mut alias = sac.clone();
+    alias.taper(0.1).unwrap();
+    let mut fraction = sac.clone();
+    fraction.taper_fraction(0.1).unwrap();
+    assert_eq!(alias, fraction);
+
+    let mut sac = sac;
+    assert!(sac.taper_samples(51).is_err());
+    assert!(sac.taper_fraction(0.6).is_err());
+}
+
+#[test]
+fn taper_plan() {
+    let traces: Vec<Sac> = (0..1000)
+        .map(|i| Sac::synthetic(vec![1.0 + i as f32; 200], 0.01, "PLAN", None).unwrap())
+        .collect();
+
+    let plan = TaperPlan::from_fraction(200, 0.05).unwrap();
+    assert_eq!(plan.trace_len(), 200);
+    assert_eq!(plan.taper_len(), 10);
+    for sac in &traces {
+        let mut planned = sac.clone();
+        planned.taper_with(&plan).unwrap();
+        let mut direct = sac.clone();
+        direct.taper(0.05).unwrap();
+        assert_eq!(planned, direct);
+    }
+
+    let mut short = Sac::synthetic(vec![1.0; 100], 0.01, "PLAN", None).unwrap();
+    assert!(short.taper_with(&plan).is_err());
+    assert!(TaperPlan::new(10, 6).is_err());
+    assert!(TaperPlan::from_fraction(10, 0.7).is_err());
+}
+
+#[test]
+fn taper_window() {
+    let ones = Sac::synthetic(vec![1.0; 8], 0.01, "HANN", None).unwrap();
+    let taper = |window| {
+        let plan = TaperPlan::with_window(8, 4, window).unwrap();
+        let mut sac = ones.clone();
+        sac.taper_with(&plan).unwrap();
+        sac.first
+    };
+
+    // A periodic plan over the whole trace is the periodic Hann window
+    let periodic = taper(TaperWindow::Periodic);
+    let expected = [0.0, 0.146447, 0.5, 0.853553, 1.0, 0.853553, 0.5, 0.146447];
+    for (v, e) in periodic.iter().zip(expected) {
+        assert!((v - e).abs() < 1e-6, "{:?}", periodic);
+    }
+
+    let symmetric = taper(TaperWindow::Symmetric);
+    assert_eq!(symmetric[..4], periodic[..4]);
+    assert_eq!(symmetric[7], 0.0);
+    assert_ne!(symmetric[7], periodic[7]);
+    assert_eq!(TaperWindow::default(), TaperWindow::Symmetric);
+    assert_eq!(
+        TaperPlan::new(8, 4).unwrap(),
+        TaperPlan::with_window(8, 4, TaperWindow::Symmetric).unwrap()
+    );
+}
+
+#[test]
+fn taper_degenerate_lengths() {
+    let trace = |len: usize| Sac::synthetic(vec![2.0; len], 0.01, "EDGE", None).unwrap();
+    for window in [TaperWindow::Symmetric, TaperWindow::Periodic] {
+        for len in [0, 1] {
+            for width in [0.0, 0.25, 0.5] {
+                let mut sac = trace(len);
+                let plan = TaperPlan::from_fraction(len, width).unwrap();
+                assert_eq!(plan.taper_len(), 0);
+                sac.taper_with(&plan).unwrap();
+                assert_eq!(sac.first, vec![2.0; len]);
+                sac.taper_fraction(width).unwrap();
+                assert_eq!(sac.first, vec![2.0; len]);
+            }
+            let plan = TaperPlan::with_window(len, 0, window).unwrap();
+            trace(len).taper_with(&plan).unwrap();
+            assert!(TaperPlan::with_window(len, 1, window).is_err());
+        }
+
+        let plan = TaperPlan::with_window(2, 1, window).unwrap();
+        let mut sac = trace(2);
+        sac.taper_with(&plan).unwrap();
+        let last = match window {
+            TaperWindow::Symmetric => 0.0,
+            TaperWindow::Periodic => 2.0,
+        };
+        assert_eq!(sac.first, vec![0.0, last]);
+        assert!(!sac.has_nonfinite());
+    }
+
+    let mut sac = trace(2);
+    sac.taper(0.0).unwrap();
+    assert_eq!(sac.first, vec![2.0, 2.0]);
+    sac.taper(0.5).unwrap();
+    assert_eq!(sac.first, vec![0.0, 0.0]);
+    assert!(sac.taper(0.5001).is_err());
+    assert!(sac.taper(1.0).is_err());
+    assert!(TaperPlan::from_fraction(2, 0.75).is_err());
+}
+
+#[test]
+fn taper_checked() {
+    let mut sac = Sac::synthetic(vec![2.0; 100], 0.01, "TAPER", None).unwrap();
+    sac.taper_checked(0.1).unwrap();
+    assert_eq!(sac.first[0], 0.0);
+
+    sac.first[50] = f32::NAN;
+    let before = sac.clone();
+    let err = sac.taper_checked(0.2).unwrap_err();
+    assert!(format!("{}", err).contains("non-finite"));
+    assert_eq!(sac, before);
+
+    // The default path stays lenient
+    sac.taper(0.2).unwrap();
+}
@@ -0,0 +1,124 @@
+use sac::{Endian, Sac, SacFileType, SacSliceExt, Waveform};
+
+struct Toy {
+    samples: Vec<f32>,
+    changed: bool,
+}
+
+impl Waveform for Toy {
+    fn data(&self) -> &[f32] {
+        &self.samples
+    }
+
+    fn data_mut(&mut self) -> &mut Vec<f32> {
+        &mut self.samples
+    }
+
+    fn delta(&self) -> f32 {
+        0.5
+    }
+
+    fn data_changed(&mut self) {
+        self.changed = true;
+    }
+}
+
+#[test]
+fn toy_demean() {
+    let mut toy = Toy {
+        samples: vec![1.0, 2.0, 3.0, 6.0],
+        changed: false,
+    };
+    toy.demean();
+
+    assert_eq!(toy.data(), [-2.0, -1.0, 0.0, 3.0]);
+    assert_eq!(toy.delta(), 0.5);
+    assert!(toy.changed);
+}
+
+#[test]
+fn toy_detrend() {
+    let mut toy = Toy {
+        samples: (0..50).map(|i| 2.0 + 0.25 * i as f32).collect(),
+        changed: false,
+    };
+    toy.detrend();
+
+    assert!(toy.data().iter().all(|v| v.abs() < 1e-5));
+}
+
+#[test]
+fn sac_demean() {
+    let mut sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    sac.demean();
+
+    let mean = sac.data().iter().map(|v| *v as f64).sum::<f64>() / sac.data().len() as f64;
+    assert!(mean.abs() < 1e-6);
+    assert!(sac.depmen.abs() < 1e-6);
+}
+
+#[test]
+fn slice_demean_all() {
+    let mut traces: Vec<Sac> = (0..3)
+        .map(|i| {
+            let mut sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+            sac.first.iter_mut().for_each(|v| *v += i as f32);
+            sac
+        })
+        .collect();
+
+    traces.demean_all();
+    for sac in &traces {
+        let mean = sac.first.iter().map(|v| *v as f64).sum::<f64>() / sac.first.len() as f64;
+        assert!(mean.abs() < 1e-6);
+    }
+
+    traces[1].iftype = SacFileType::AmpPhase;
+    let mut calls = 0;
+    let result = traces.try_apply_all(|sac| {
+        calls += 1;
+        sac.normalize_rms(1.0)
+    });
+    assert!(result.is_err());
+    assert_eq!(calls, 2);
+}
+
+#[test]
+fn toy_detrend_coeffs() {
+    // 3 + 4t sampled every 0.5 s
+    let mut toy = Toy {
+        samples: (0..20).map(|i| 3.0 + 4.0 * i as f32 * 0.5).collect(),
+        changed: false,
+    };
+
+    let (slope, intercept) = toy.detrend_coeffs();
+    assert!((slope - 4.0).abs() < 1e-4);
+    assert!((intercept - 3.0).abs() < 1e-4);
+    assert!(toy.samples.iter().all(|v| v.abs() < 1e-4));
+    assert!(toy.changed);
+}
+
+#[test]
+fn sac_copies() {
+    let mut sac = Sac::from_file("tests/test.sac", Endian::Little).unwrap();
+    sac.first
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, v)| *v += i as f32);
+    let original = sac.clone();
+
+    let mut expected = sac.clone();
+    expected.demean();
+    assert_eq!(sac.demeaned(), expected);
+
+    let mut expected = sac.clone();
+    expected.detrend();
+    assert_eq!(sac.detrended(), expected);
+
+    let mut expected = sac.clone();
+    expected.taper(0.1).unwrap();
+    assert_eq!(sac.tapered(0.1).unwrap(), expected);
+    assert!(sac.tapered(0.7).is_err());
+
+    assert_eq!(sac, original);
+}